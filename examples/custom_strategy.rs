@@ -0,0 +1,47 @@
+//! Plays a hand written strategy against the random computer player.
+//! <br><br>
+//! Run it with `cargo run --example custom_strategy`.
+
+use tic_tac_toe::{check_win, empty_cells, index_to_coord, is_full, Game, RandomAi, Rng, Seating, Strategy};
+
+/// Takes the centre if it can, then the corners, then whatever is left.
+struct CentreFirst;
+
+impl Strategy for CentreFirst {
+    fn choose(&mut self, game: &Game) -> usize {
+        let free = empty_cells(game.board());
+
+        for index in [5, 1, 3, 7, 9] {
+            if free.contains(&index) {
+                return index;
+            }
+        }
+
+        free[0]
+    }
+}
+
+fn main() {
+    let mut seating = Seating::new(Box::new(CentreFirst), Box::new(RandomAi::new(Rng::from_time())));
+
+    let mut game = Game::new();
+
+    loop {
+        let player = *game.current_player();
+        let index = seating.seat(player).choose(&game);
+        let (row, cell) = index_to_coord(index).expect("strategies only choose cells on the board");
+
+        game.make_move(row, cell, player.to_cell()).expect("strategies only choose empty cells");
+        println!("{} plays {}", player.to_letter(), index);
+
+        if let Some(win) = check_win(game.board(), game.rules()) {
+            println!("{} wins!", win.player.to_letter());
+            break;
+        }
+
+        if is_full(game.board()) {
+            println!("It's a draw!");
+            break;
+        }
+    }
+}
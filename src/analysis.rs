@@ -0,0 +1,396 @@
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use crate::board::{board_to_line, coord_to_index, empty_cells, index_to_coord, symmetries, Board, CellState, Player, Rules};
+use crate::game::{Game, GameOutcome, Move};
+use crate::rng::Rng;
+use crate::strategy::evaluate;
+
+/// The rotation or reflection of the board that comes first when written in one-line form.
+/// <br><br>
+/// Boards that are symmetries of each other all share the same canonical board.
+pub fn canonical(board: &Board) -> Board {
+    let mut best = *board;
+    let mut best_line = board_to_line(board);
+
+    for symmetry in symmetries(board) {
+        let line = board_to_line(&symmetry);
+
+        if line < best_line {
+            best = symmetry;
+            best_line = line;
+        }
+    }
+
+    best
+}
+
+/// The player whose turn it is, worked out from how many marks each side has played.
+/// <br><br>
+/// That's only right for boards reached without passing, a [`Game`] keeps track of whose turn it really is.
+pub fn to_move(board: &Board) -> Player {
+    if board.count(&CellState::Cross) > board.count(&CellState::Nought) { Player::Noughts } else { Player::Crosses }
+}
+
+/// Every position that can come up in a game by the given rules, with how it ends under perfect play.
+/// <br><br>
+/// Positions that are rotations or reflections of each other are only listed once, as their [`canonical`] board.
+/// They're ordered by how many marks have been played, then by their one-line form.
+/// Rolling games never run out of positions to reach, so they can't be listed.
+pub fn tablebase(rules: &Rules) -> Vec<(Board, GameOutcome)> {
+    let mut positions = BTreeMap::new();
+
+    collect_positions(&mut Game::with_rules(*rules), &mut positions);
+
+    positions.into_values().collect()
+}
+
+fn collect_positions(game: &mut Game, positions: &mut BTreeMap<(usize, String), (Board, GameOutcome)>) {
+    let canonical = canonical(&game.board);
+    let key = (9 - empty_cells(&game.board).len(), board_to_line(&canonical));
+
+    if positions.contains_key(&key) {
+        return;
+    }
+
+    positions.insert(key, (canonical, evaluate(game)));
+
+    if game.outcome().is_some() {
+        return;
+    }
+
+    let placements: Vec<(usize, CellState)> = game.placements().collect();
+
+    for (index, mark) in placements {
+        let (row, cell) = index_to_coord(index).expect("empty cells are always on the board");
+
+        let rolled = game.place(row, cell, mark, Duration::ZERO);
+        collect_positions(game, positions);
+        game.unplay(rolled);
+    }
+}
+
+/// Picks `depth` moves from the empty board at random, Crosses first, returning their 1-based cell indices.
+/// <br><br>
+/// Only moves that keep the game drawn under perfect play are picked, so neither side starts out already lost.
+/// A drawn position always has at least one such move, and the same generator state always gives the same opening.
+/// Holes can leave a board that isn't drawn to begin with, and then the moves keep whatever result it has,
+/// stopping short of `depth` rather than play the move that would end the game.
+pub fn random_opening(rng: &mut Rng, depth: usize, rules: &Rules) -> Vec<usize> {
+    let mut game = Game::with_rules(*rules);
+    let mut moves = Vec::new();
+
+    while moves.len() < depth && game.outcome().is_none() {
+        let value = evaluate(&game);
+
+        let mut keeping: Vec<(usize, Game)> = game
+            .legal_moves()
+            .map(|index| (index, game.clone_with_move(index).expect("legal moves are always playable")))
+            .filter(|(_, next)| next.outcome().is_none() && evaluate(next) == value)
+            .collect();
+
+        if keeping.is_empty() {
+            break;
+        }
+
+        let (index, next) = keeping.swap_remove(rng.below(keeping.len()));
+
+        game = next;
+        moves.push(index);
+    }
+
+    moves
+}
+
+/// The size of the game tree below a position.
+pub struct TreeSize {
+    /// Distinct ways the game can be played out to a win or a draw.
+    pub games: u64,
+    /// Distinct positions along the way, including the starting one.
+    pub positions: usize,
+    /// Distinct positions once rotations and reflections are counted as the same.
+    pub canonical_positions: usize,
+}
+
+/// Counts every way `game` can be played out under the normal rules, along with the positions it passes through.
+/// <br><br>
+/// From the empty board this is 255,168 games through 5,478 positions, or 765 up to symmetry.
+pub fn count_games(game: &Game) -> TreeSize {
+    let mut positions = HashSet::new();
+    let mut canonical_positions = HashSet::new();
+
+    let games = count_from(game, &mut positions, &mut canonical_positions);
+
+    TreeSize { games, positions: positions.len(), canonical_positions: canonical_positions.len() }
+}
+
+fn count_from(game: &Game, positions: &mut HashSet<String>, canonical_positions: &mut HashSet<String>) -> u64 {
+    // Most positions are reached many times over, so only work out the symmetry the first time
+    if positions.insert(board_to_line(&game.board)) {
+        canonical_positions.insert(board_to_line(&canonical(&game.board)));
+    }
+
+    if game.outcome().is_some() {
+        return 1;
+    }
+
+    let mut games = 0;
+
+    for index in game.legal_moves() {
+        let next = game.clone_with_move(index).expect("legal moves are always playable");
+
+        games += count_from(&next, positions, canonical_positions);
+    }
+
+    games
+}
+
+/// Plays `moves` random legal moves from the empty board, Crosses first, returning their 1-based cell indices.
+/// <br><br>
+/// Unlike [`random_opening`] the position can favour either side, but finished games are thrown away
+/// and tried again, so the game is always still going. The same generator state always gives the same moves.
+/// Boards with holes may not have room for `moves` moves, and then get one fewer than they have empty cells.
+/// A move limit ends the game as a draw, so games with one get at most one fewer than the limit.
+pub fn random_midgame(rng: &mut Rng, moves: usize, rules: &Rules) -> Vec<usize> {
+    let room = rules.empty_board().count(&CellState::Empty).min(rules.move_limit.unwrap_or(usize::MAX));
+    let moves = moves.min(room.saturating_sub(1));
+
+    loop {
+        let mut game = Game::with_rules(*rules);
+        let mut played = Vec::new();
+
+        while played.len() < moves && game.outcome().is_none() {
+            let cells: Vec<usize> = game.legal_moves().collect();
+            let index = cells[rng.below(cells.len())];
+
+            game = game.clone_with_move(index).expect("legal moves are always empty cells");
+            played.push(index);
+        }
+
+        if game.outcome().is_none() {
+            return played;
+        }
+    }
+}
+
+/// How a move compared with the best one the player had.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MoveQuality {
+    /// Kept the best result on offer.
+    Good,
+    /// Let a win slip to a draw.
+    Inaccuracy,
+    /// Turned a win or a draw into a loss.
+    Blunder,
+}
+
+impl MoveQuality {
+    pub fn to_name(self) -> &'static str {
+        match self {
+            MoveQuality::Good => "good",
+            MoveQuality::Inaccuracy => "inaccuracy",
+            MoveQuality::Blunder => "blunder"
+        }
+    }
+}
+
+/// Rates the player to move placing `mark` in the cell with the given 1-based index, by comparing the result
+/// it leads to under perfect play (see [`evaluate`]) with the result of their best move.
+pub fn rate_move(game: &Game, index: usize, mark: CellState) -> MoveQuality {
+    let player = game.player;
+
+    // Higher is better for the player: a loss, a draw, then a win
+    let result_after = |(index, mark): (usize, CellState)| {
+        let mut next = game.clone();
+
+        match index_to_coord(index).map(|(row, cell)| next.make_move(row, cell, mark)) {
+            Some(Ok(())) => {}
+            _ => return 0
+        }
+
+        match evaluate(&next) {
+            GameOutcome::Win(winner) if winner == player => 2,
+            GameOutcome::Draw => 1,
+            GameOutcome::Win(_) => 0
+        }
+    };
+
+    let played = result_after((index, mark));
+    let best = game.placements().map(result_after).max().unwrap_or(played);
+
+    match (best, played) {
+        _ if played >= best => MoveQuality::Good,
+        (_, 0) => MoveQuality::Blunder,
+        _ => MoveQuality::Inaccuracy
+    }
+}
+
+/// The move the player to move has no real choice about, as a 1-based cell and the mark to place there, if there is one.
+/// <br><br>
+/// A move is forced when it's the only one there is, or when it's the only move that doesn't lose
+/// with perfect play (see [`evaluate`]) and the player isn't already lost whatever they do.
+pub fn forced_move(game: &Game) -> Option<(usize, CellState)> {
+    let opponent = game.player.other();
+    let moves: Vec<(usize, CellState)> = game.placements().collect();
+
+    if game.outcome().is_some() {
+        return None;
+    }
+
+    if let [only] = moves[..] {
+        return Some(only);
+    }
+
+    if evaluate(game) == GameOutcome::Win(opponent) {
+        return None;
+    }
+
+    let safe: Vec<(usize, CellState)> = moves
+        .into_iter()
+        .filter(|&(index, mark)| {
+            let mut next = game.clone();
+            let (row, cell) = index_to_coord(index).expect("empty cells are always on the board");
+
+            next.make_move(row, cell, mark).expect("the game's own placements are always legal");
+            evaluate(&next) != GameOutcome::Win(opponent)
+        })
+        .collect();
+
+    match safe[..] {
+        [only] => Some(only),
+        _ => None
+    }
+}
+
+/// Replays the game from the empty board, rating every mark placed as the player, their 1-based cell and its [`MoveQuality`].
+pub fn analyze_game(game: &Game) -> Vec<(Player, usize, MoveQuality)> {
+    let mut replay = Game::with_rules(game.rules);
+    let mut ratings = Vec::new();
+
+    for turn in &game.history {
+        match turn {
+            Move::Place(player, row, cell, mark) => {
+                let index = coord_to_index(*row, *cell);
+
+                ratings.push((*player, index, rate_move(&replay, index, *mark)));
+
+                replay.player = *player;
+                replay.make_move(*row, *cell, *mark).expect("the game's own moves are always legal");
+            }
+            Move::Pass => replay.pass()
+        }
+    }
+
+    ratings
+}
+
+/// Whether the result of the game is already settled, even if the board isn't full.
+/// <br><br>
+/// That's the case when the player to move has a forced win, when the other player does, or when
+/// no way of carrying on lets anyone complete a line, so the game can only be drawn.
+/// A position that's only drawn with best play isn't decided, since one side could still make a mistake.
+/// In rolling games that only counts wins [`evaluate`] can see coming.
+pub fn is_decided(game: &Game) -> bool {
+    if game.outcome().is_some() {
+        return true;
+    }
+
+    match evaluate(game) {
+        GameOutcome::Win(_) => true,
+        GameOutcome::Draw => is_dead_draw(game)
+    }
+}
+
+/// Whether the game can only end in a draw, because no way of playing on, however bad, completes a line.
+/// <br><br>
+/// A game that has already been won isn't a draw, dead or otherwise.
+/// Marks coming off the board can open lines up again, so rolling games never count as dead draws.
+pub fn is_dead_draw(game: &Game) -> bool {
+    !game.rules.rolling && !win_possible(&mut game.clone())
+}
+
+/// Whether any way of playing on from here by the game's rules, good or bad, ends with someone completing a line.
+fn win_possible(game: &mut Game) -> bool {
+    match game.outcome() {
+        Some(GameOutcome::Win(_)) => return true,
+        Some(GameOutcome::Draw) => return false,
+        None => {}
+    }
+
+    let placements: Vec<(usize, CellState)> = game.placements().collect();
+
+    placements.into_iter().any(|(index, mark)| {
+        let (row, cell) = index_to_coord(index).expect("empty cells are always on the board");
+
+        let rolled = game.place(row, cell, mark, Duration::ZERO);
+        let possible = win_possible(game);
+        game.unplay(rolled);
+
+        possible
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::index_to_coord;
+
+    /// A standard game after each cell in turn is played with the mover's own mark.
+    fn after(cells: &[usize]) -> Game {
+        let mut game = Game::new();
+
+        for index in cells {
+            let (row, cell) = index_to_coord(*index).unwrap();
+
+            game.make_move(row, cell, game.player.to_cell()).unwrap();
+        }
+
+        game
+    }
+
+    #[test]
+    fn a_forced_win_is_decided_before_the_board_fills() {
+        // Noughts answered the corner with the edge next to it, and crosses wins from here however noughts plays
+        let game = after(&[1, 2]);
+
+        assert!(is_decided(&game));
+        assert_eq!(evaluate(&game), GameOutcome::Win(Player::Crosses));
+        assert_eq!(game.outcome(), None);
+    }
+
+    #[test]
+    fn only_dead_draws_are_decided_draws() {
+        // Drawn with best play, but either side can still lose by a mistake
+        assert!(!is_decided(&Game::new()));
+
+        // The last free cell doesn't complete any line
+        let game = after(&[1, 2, 4, 5, 3, 6, 8, 7]);
+
+        assert!(is_decided(&game));
+        assert!(is_dead_draw(&game));
+    }
+
+    #[test]
+    fn a_pass_leaves_the_decision_to_the_player_who_really_moves() {
+        // Counting marks says noughts are to move and hold the draw, but they passed and crosses forks with 3
+        let mut game = after(&[1, 5, 9]);
+        game.pass();
+
+        assert_eq!(to_move(&game.board), Player::Noughts);
+        assert!(!is_decided(&after(&[1, 5, 9])));
+        assert!(is_decided(&game));
+    }
+
+    #[test]
+    fn random_midgames_stop_short_of_the_move_limit() {
+        for limit in 1..=4 {
+            let rules = Rules { move_limit: Some(limit), ..Rules::standard() };
+
+            for seed in 0..20 {
+                let moves = random_midgame(&mut Rng::new(seed), 6, &rules);
+
+                assert_eq!(moves.len(), limit - 1, "seed {} with a limit of {}", seed, limit);
+            }
+        }
+    }
+}
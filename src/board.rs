@@ -0,0 +1,613 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::sync::{Mutex, OnceLock};
+
+/// The board of a tic tac toe game.
+/// <br><br>
+/// Below is a visual of the cell indices (formatted board\[row]\[column]):
+/// ```text
+/// [1] [2] [3] < -- 0
+/// [4] [5] [6] < -- 1
+/// [7] [8] [9] < -- 2
+///  ^   ^   ^
+///  |   |   |
+///  0   1   2
+/// ```
+/// Cells can be read and written by their 1-based index with [`Board::get`] and [`Board::set`],
+/// or by coordinates as `board[row][column]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Board([[CellState; 3]; 3]);
+
+impl Board {
+    /// A board with every cell empty.
+    pub fn new() -> Board {
+        Board([[CellState::Empty; 3]; 3])
+    }
+
+    /// The cell with the given 1-based index, or `None` if there's no such cell.
+    pub fn get(&self, index: usize) -> Option<&CellState> {
+        index_to_coord(index).map(|(row, col)| &self.0[row][col])
+    }
+
+    /// Replaces the cell with the given 1-based index, whatever was in it before.
+    pub fn set(&mut self, index: usize, state: CellState) -> Result<(), GameError> {
+        let (row, col) = index_to_coord(index).ok_or(GameError::OffBoard(index))?;
+
+        self.0[row][col] = state;
+
+        Ok(())
+    }
+
+    /// How many cells hold `state`.
+    pub fn count(&self, state: &CellState) -> usize {
+        self.0.iter().flatten().filter(|cell| *cell == state).count()
+    }
+
+    /// The rows of the board, from top to bottom.
+    pub fn iter(&self) -> std::slice::Iter<'_, [CellState; 3]> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, [CellState; 3]> {
+        self.0.iter_mut()
+    }
+}
+
+impl Default for Board {
+    fn default() -> Board {
+        Board::new()
+    }
+}
+
+impl Index<usize> for Board {
+    type Output = [CellState; 3];
+
+    fn index(&self, row: usize) -> &[CellState; 3] {
+        &self.0[row]
+    }
+}
+
+impl IndexMut<usize> for Board {
+    fn index_mut(&mut self, row: usize) -> &mut [CellState; 3] {
+        &mut self.0[row]
+    }
+}
+
+/// A change to the board that couldn't be made.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameError {
+    /// There is no cell with this 1-based index.
+    OffBoard(usize),
+    /// The cell with this 1-based index already has a mark in it.
+    Occupied(usize),
+    /// The cell with this 1-based index is a hole nobody can play in.
+    Blocked(usize),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::OffBoard(index) => write!(f, "There is no cell {}!", index),
+            GameError::Occupied(index) => write!(f, "Cell {} has already been played!", index),
+            GameError::Blocked(index) => write!(f, "Cell {} is blocked!", index)
+        }
+    }
+}
+
+impl Error for GameError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Player {
+    Noughts,
+    Crosses,
+}
+
+impl Player {
+    pub fn to_letter(self) -> char {
+        match self {
+            Player::Noughts => 'O',
+            Player::Crosses => 'X'
+        }
+    }
+
+    pub fn to_name(self) -> &'static str {
+        match self {
+            Player::Noughts => "Noughts",
+            Player::Crosses => "Crosses"
+        }
+    }
+
+    pub fn to_number(self) -> i32 {
+        match self {
+            Player::Noughts => 2,
+            Player::Crosses => 1
+        }
+    }
+
+    pub fn to_cell(self) -> CellState {
+        match self {
+            Player::Noughts => CellState::Nought,
+            Player::Crosses => CellState::Cross
+        }
+    }
+
+    pub fn other(self) -> Player {
+        match self {
+            Player::Noughts => Player::Crosses,
+            Player::Crosses => Player::Noughts
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CellState {
+    Empty,
+    Nought,
+    Cross,
+    /// A hole in the board that nobody can play in, see [`Rules::holes`].
+    Blocked,
+}
+
+impl CellState {
+    pub fn to_player(self) -> Result<Player, &'static str> {
+        match self {
+            CellState::Empty => Err("Cell has not been played!"),
+            CellState::Blocked => Err("Cell is blocked!"),
+            CellState::Nought => Ok(Player::Noughts),
+            CellState::Cross => Ok(Player::Crosses)
+        }
+    }
+}
+
+/// Converts a 1-based cell index (as shown on the board) to its (row, column) coordinates.
+pub fn index_to_coord(index: usize) -> Option<(usize, usize)> {
+    match index {
+        1..=9 => Some(((index - 1) / 3, (index - 1) % 3)),
+        _ => None
+    }
+}
+
+/// Converts (row, column) coordinates to the 1-based cell index shown on the board.
+pub fn coord_to_index(row: usize, col: usize) -> usize {
+    row * 3 + col + 1
+}
+
+/// The 1-based indices of every cell that hasn't been played yet.
+pub fn empty_cells(board: &Board) -> Vec<usize> {
+    board
+        .iter()
+        .flatten()
+        .enumerate()
+        .filter(|(_, cell)| cell == &&CellState::Empty)
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+pub fn is_full(board: &Board) -> bool {
+    board.iter().flatten().all(|cell| cell != &CellState::Empty)
+}
+
+/// Writes the board on a single line, such as `X.O|..X|O..`.
+/// <br><br>
+/// Marks are written as `X` and `O`, empty cells as `.` and rows are separated by `|`.
+pub fn board_to_line(board: &Board) -> String {
+    let rows: Vec<String> = board
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    CellState::Empty => '.',
+                    CellState::Blocked => '#',
+                    CellState::Nought => 'O',
+                    CellState::Cross => 'X'
+                })
+                .collect()
+        })
+        .collect();
+
+    rows.join("|")
+}
+
+/// Reads a board written by [`board_to_line`].
+pub fn board_from_line(line: &str) -> Result<Board, &'static str> {
+    let mut board = Board::new();
+    let rows: Vec<&str> = line.trim().split('|').collect();
+
+    if rows.len() != 3 {
+        return Err("A board needs three rows separated by `|`!");
+    }
+
+    for (i_row, row) in rows.iter().enumerate() {
+        let cells: Vec<char> = row.chars().collect();
+
+        if cells.len() != 3 {
+            return Err("Every row needs exactly three cells!");
+        }
+
+        for (i_col, cell) in cells.iter().enumerate() {
+            board[i_row][i_col] = match cell {
+                '.' => CellState::Empty,
+                '#' => CellState::Blocked,
+                'O' | 'o' => CellState::Nought,
+                'X' | 'x' => CellState::Cross,
+                _ => return Err("Cells must be `X`, `O`, `.` or `#`!")
+            };
+        }
+    }
+
+    Ok(board)
+}
+
+/// The cells that are different on `after`, as their 1-based index and what's in them now, in order.
+/// <br><br>
+/// Normally that's the one mark just placed, but in rolling games a cell can also have been emptied.
+pub fn board_diff(before: &Board, after: &Board) -> Vec<(usize, CellState)> {
+    before
+        .iter()
+        .flatten()
+        .zip(after.iter().flatten())
+        .enumerate()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(i, (_, new))| (i + 1, *new))
+        .collect()
+}
+
+/// Turns the board a quarter turn clockwise.
+pub fn rotate90(board: &Board) -> Board {
+    let mut rotated = *board;
+
+    for (i_row, row) in rotated.iter_mut().enumerate() {
+        for (i_col, cell) in row.iter_mut().enumerate() {
+            *cell = board[2 - i_col][i_row];
+        }
+    }
+
+    rotated
+}
+
+/// Flips the board left to right, so the first and last columns swap.
+pub fn reflect_horizontal(board: &Board) -> Board {
+    let mut reflected = *board;
+
+    for row in reflected.iter_mut() {
+        row.reverse();
+    }
+
+    reflected
+}
+
+/// Every rotation and reflection of the board, starting with the board itself.
+/// <br><br>
+/// The first four are the board turned 0, 90, 180 and 270 degrees clockwise,
+/// the last four are the same turns applied to the mirrored board.
+pub fn symmetries(board: &Board) -> [Board; 8] {
+    let mut all = [*board; 8];
+
+    all[4] = reflect_horizontal(board);
+
+    for i in [1, 2, 3, 5, 6, 7] {
+        all[i] = rotate90(&all[i - 1]);
+    }
+
+    all
+}
+
+/// The coordinates of three cells in a row.
+pub type Line = [(usize, usize); 3];
+
+/// Every run of `k` cells in a straight line on a board `size` cells wide, as lists of 1-based cell indices.
+/// <br><br>
+/// They go down, then across, then diagonally down to the right and down to the left, each starting from the top left.
+/// So `lines(3, 3)` is every line that wins a normal game, the ones [`Rules::lines`] hands out:
+/// ```text
+/// [1, 4, 7], [2, 5, 8], [3, 6, 9], [1, 2, 3], [4, 5, 6], [7, 8, 9], [1, 5, 9], [3, 5, 7]
+/// ```
+/// The lines for each size are only worked out the first time they're asked for, and kept for the rest of the program
+/// so every later call hands back the same list without copying it.
+pub fn lines(size: usize, k: usize) -> &'static [Vec<usize>] {
+    /// The lines already found, by board size and run length.
+    type Cache = BTreeMap<(usize, usize), &'static [Vec<usize>]>;
+
+    static CACHE: Mutex<Cache> = Mutex::new(BTreeMap::new());
+
+    // A poisoned cache still holds whole lists, the lock is only there so threads don't build the same one twice
+    let mut cache = CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    cache.entry((size, k)).or_insert_with(|| find_lines(size, k).leak())
+}
+
+fn find_lines(size: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut found = Vec::new();
+
+    if k == 0 || k > size {
+        return found;
+    }
+
+    let size = size as isize;
+    let k = k as isize;
+
+    for (row_step, col_step) in [(1, 0), (0, 1), (1, 1), (1, -1)] {
+        for row in 0..size {
+            for col in 0..size {
+                let (last_row, last_col) = (row + row_step * (k - 1), col + col_step * (k - 1));
+
+                if (0..size).contains(&last_row) && (0..size).contains(&last_col) {
+                    found.push((0..k).map(|i| ((row + row_step * i) * size + col + col_step * i + 1) as usize).collect());
+                }
+            }
+        }
+    }
+
+    found
+}
+
+/// The variant of the rules a game is played by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rules {
+    /// Whether the two diagonals count as winning lines, some children's versions only use rows and columns.
+    pub diagonals: bool,
+    /// Whether each player only keeps their three newest marks, so a fourth mark clears their oldest one.
+    pub rolling: bool,
+    /// The cells that are holes nobody can play in, with cell `i` at `holes[i - 1]`.
+    /// <br><br>
+    /// A hole counts as filled, so lines through it can never be completed and a board of marks and holes is a draw.
+    pub holes: [bool; 9],
+    /// Whether players can place either mark, so a line belongs to whoever completed it rather than to whose marks fill it.
+    pub wild: bool,
+    /// How many moves a game can last before it's a draw, whether or not the board is full.
+    pub move_limit: Option<usize>,
+}
+
+impl Rules {
+    /// The normal rules, where any row, column or diagonal wins.
+    pub fn standard() -> Rules {
+        Rules { diagonals: true, rolling: false, holes: [false; 9], wild: false, move_limit: None }
+    }
+
+    /// The board games under these rules start from, empty apart from its holes.
+    pub fn empty_board(&self) -> Board {
+        let mut board = Board::new();
+
+        for (i, _) in self.holes.iter().enumerate().filter(|(_, hole)| **hole) {
+            board.set(i + 1, CellState::Blocked).expect("holes are always on the board");
+        }
+
+        board
+    }
+
+    /// The lines that win the game under these rules, the columns, then the rows, then the two diagonals.
+    pub fn lines(&self) -> &'static [Line] {
+        static ALL: OnceLock<Vec<Line>> = OnceLock::new();
+
+        let all = ALL.get_or_init(|| {
+            lines(3, 3)
+                .iter()
+                .map(|cells| [0, 1, 2].map(|i| index_to_coord(cells[i]).expect("lines only run through cells on the board")))
+                .collect()
+        });
+
+        // The diagonals come last, so leaving them out is just a shorter slice
+        if self.diagonals { all } else { &all[..6] }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules::standard()
+    }
+}
+
+/// Every line filled with a single player's marks.
+/// <br><br>
+/// A real game ends at the first one, but hand made boards can have several.
+pub fn completed_lines(board: &Board, rules: &Rules) -> Vec<(Line, Player)> {
+    rules
+        .lines()
+        .iter()
+        .filter_map(|line| {
+            let [a, b, c] = line.map(|(row, col)| board[row][col]);
+
+            if a == b && b == c {
+                a.to_player().ok().map(|player| (*line, player))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// The player whose mark in the cell with the given 1-based index completes a line, if it does.
+/// <br><br>
+/// A new win has to use the mark just placed, so after a move this gives the same answer as [`check_win`]
+/// of a game that wasn't already won, while only looking at the lines through that cell.
+/// <br><br>
+/// The player named is the one the mark belongs to. In wild games either player can place either mark,
+/// so the winner is whoever placed it, as [`Game::outcome`](crate::Game::outcome) has it, which can be the other player.
+pub fn check_win_at(board: &Board, last_move: usize, rules: &Rules) -> Option<Player> {
+    let placed = index_to_coord(last_move)?;
+    let player = board[placed.0][placed.1].to_player().ok()?;
+
+    rules
+        .lines()
+        .iter()
+        .filter(|line| line.contains(&placed))
+        .any(|line| line.iter().all(|(row, col)| board[*row][*col] == player.to_cell()))
+        .then_some(player)
+}
+
+/// The 1-based indices of every empty cell that would complete a line for `player`.
+pub fn winning_moves(board: &Board, player: Player, rules: &Rules) -> Vec<usize> {
+    let mut cells = Vec::new();
+
+    for line in rules.lines() {
+        let marks = line.iter().filter(|(row, col)| board[*row][*col] == player.to_cell()).count();
+        let empty = line.iter().find(|(row, col)| board[*row][*col] == CellState::Empty);
+
+        if let (2, Some((row, col))) = (marks, empty) {
+            let index = coord_to_index(*row, *col);
+
+            // A cell can finish more than one line, but it's still only one move
+            if !cells.contains(&index) {
+                cells.push(index);
+            }
+        }
+    }
+
+    cells.sort_unstable();
+    cells
+}
+
+/// Whether playing in the cell with the given 1-based index leaves `player` with two or more ways to win at once.
+/// <br><br>
+/// Only genuine forks count, where the opponent can't win straight back and can block just one of the threats.
+pub fn creates_fork(board: &Board, index: usize, player: Player, rules: &Rules) -> bool {
+    let mut after = *board;
+
+    if board.get(index) != Some(&CellState::Empty) || after.set(index, player.to_cell()).is_err() {
+        return false;
+    }
+
+    winning_moves(&after, player, rules).len() >= 2 && winning_moves(&after, player.other(), rules).is_empty()
+}
+
+/// The line that won the game and whose marks fill it.
+#[derive(Clone, Copy, PartialEq)]
+pub struct WinInfo {
+    pub player: Player,
+    pub line: Line,
+}
+
+pub fn check_win(board: &Board, rules: &Rules) -> Option<WinInfo> {
+    completed_lines(board, rules).first().map(|(line, player)| WinInfo { player: *player, line: *line })
+}
+
+/// Names a line on a board `size` cells wide, such as "the left column" or "the main diagonal".
+pub fn line_name(line: &Line, size: usize) -> String {
+    let [(first_row, first_col), ..] = *line;
+
+    let position = |i: usize, names: [&str; 3]| {
+        if i == 0 {
+            String::from(names[0])
+        } else if i + 1 == size {
+            String::from(names[2])
+        } else if size % 2 == 1 && i == size / 2 {
+            String::from(names[1])
+        } else {
+            format!("{}", i + 1)
+        }
+    };
+
+    if line.iter().all(|(row, _)| *row == first_row) {
+        format!("the {} row", position(first_row, ["top", "middle", "bottom"]))
+    } else if line.iter().all(|(_, col)| *col == first_col) {
+        format!("the {} column", position(first_col, ["left", "middle", "right"]))
+    } else if line.iter().all(|(row, col)| row == col) {
+        String::from("the main diagonal")
+    } else {
+        String::from("the anti-diagonal")
+    }
+}
+
+/// Names the winning line and its cells on a board `size` cells wide, such as "the left column (cells 1, 4, 7)".
+pub fn describe_win(info: &WinInfo, size: usize) -> String {
+    let cells: Vec<String> = info.line.iter().map(|(row, col)| (row * size + col + 1).to_string()).collect();
+
+    format!("{} (cells {})", line_name(&info.line, size), cells.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::rng::Rng;
+
+    #[test]
+    fn four_quarter_turns_give_back_the_board() {
+        let board = board_from_line("XO.|..X|#O.").unwrap();
+
+        assert_eq!(board_to_line(&rotate90(&board)), "#.X|O.O|.X.");
+        assert_eq!(rotate90(&rotate90(&rotate90(&rotate90(&board)))), board);
+    }
+
+    #[test]
+    fn symmetries_are_the_turns_of_the_board_and_its_mirror() {
+        let board = board_from_line("XO.|..X|#O.").unwrap();
+        let all = symmetries(&board);
+
+        assert_eq!(all[0], board);
+        assert_eq!(all[4], reflect_horizontal(&board));
+        assert_eq!(reflect_horizontal(&all[4]), board);
+
+        for i in [1, 2, 3, 5, 6, 7] {
+            assert_eq!(all[i], rotate90(&all[i - 1]));
+        }
+    }
+
+    #[test]
+    fn board_diff_gives_the_placed_mark() {
+        let before = board_from_line("X..|.O.|...").unwrap();
+        let after = board_from_line("X..|.O.|..X").unwrap();
+
+        assert_eq!(board_diff(&before, &after), [(9, CellState::Cross)]);
+        assert_eq!(board_diff(&after, &after), []);
+    }
+
+    #[test]
+    fn board_diff_gives_the_cleared_cell_in_rolling_games() {
+        let mut game = Game::with_rules(Rules { rolling: true, ..Rules::standard() });
+
+        for index in [1, 2, 4, 5, 8, 3] {
+            let (row, col) = index_to_coord(index).unwrap();
+
+            game.make_move(row, col, game.current_player().to_cell()).unwrap();
+        }
+
+        // Crosses' fourth mark takes their first one, in cell 1, off the board
+        let before = *game.board();
+        game.make_move(2, 0, CellState::Cross).unwrap();
+
+        assert_eq!(board_diff(&before, game.board()), [(1, CellState::Empty), (7, CellState::Cross)]);
+    }
+
+    #[test]
+    fn check_win_at_agrees_with_check_win() {
+        // No holes, the centre, a corner and two opposite corners, each taking different lines out
+        let masks: [&[usize]; 4] = [&[], &[5], &[1], &[3, 7]];
+        let mut variants = Vec::new();
+
+        for diagonals in [true, false] {
+            for mask in masks {
+                let mut holes = [false; 9];
+
+                for index in mask {
+                    holes[index - 1] = true;
+                }
+
+                variants.push(Rules { diagonals, holes, ..Rules::standard() });
+            }
+        }
+
+        for rules in variants {
+            for seed in 0..500 {
+                let mut rng = Rng::new(seed);
+                let mut board = rules.empty_board();
+                let mut player = Player::Crosses;
+
+                // Only until the first line, after that the last move isn't the only way a line could have been made
+                while check_win(&board, &rules).is_none() && !is_full(&board) {
+                    let free = empty_cells(&board);
+                    let index = free[rng.below(free.len())];
+
+                    board.set(index, player.to_cell()).unwrap();
+                    player = player.other();
+
+                    let full = check_win(&board, &rules).map(|win| win.player);
+
+                    assert_eq!(check_win_at(&board, index, &rules), full, "{} after {} with seed {} and {:?}", board_to_line(&board), index, seed, rules);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,652 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::board::{check_win, check_win_at, coord_to_index, index_to_coord, is_full, Board, CellState, GameError, Player, Rules};
+
+/// A single turn taken by a player.
+#[derive(Clone, PartialEq)]
+pub enum Move {
+    /// The player placed a mark at board\[row]\[column], which in wild games can be the other player's.
+    Place(Player, usize, usize, CellState),
+    Pass,
+}
+
+/// How a game ended, or how it will end with perfect play.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameOutcome {
+    Win(Player),
+    Draw,
+}
+
+/// How many marks each player keeps on the board in rolling games.
+pub const ROLLING_MARKS: usize = 3;
+
+/// A game in progress.
+/// <br><br>
+/// Its state can be read through accessors like [`Game::board`] but only changed by playing moves,
+/// so a front end can't put the board into a position the game never reached.
+#[derive(Clone)]
+pub struct Game {
+    pub(crate) board: Board,
+    pub(crate) player: Player,
+    pub(crate) history: Vec<Move>,
+    pub(crate) rules: Rules,
+    /// The 1-based cell of each placed mark and how long the player took over it.
+    times: Vec<(usize, Duration)>,
+    /// The (row, column) of each player's marks still on the board, oldest first, for rolling games.
+    crosses_placed: VecDeque<(usize, usize)>,
+    noughts_placed: VecDeque<(usize, usize)>,
+    /// What [`Game::outcome`] returns, worked out again after every move so the board isn't searched on each call.
+    outcome: Option<GameOutcome>,
+}
+
+impl Game {
+    /// An empty board with Crosses to play first.
+    pub fn new() -> Game {
+        Game::with_rules(Rules::standard())
+    }
+
+    /// The same as [`Game::new`], but played by a variant of the rules.
+    pub fn with_rules(rules: Rules) -> Game {
+        Game {
+            board: rules.empty_board(),
+            player: Player::Crosses,
+            history: Vec::new(),
+            rules,
+            times: Vec::new(),
+            crosses_placed: VecDeque::new(),
+            noughts_placed: VecDeque::new(),
+            outcome: None,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The player whose turn it is.
+    pub fn current_player(&self) -> &Player {
+        &self.player
+    }
+
+    /// Every turn taken so far, oldest first.
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Hands the turn to the other player, which outside the crate only happens by playing a move or passing.
+    pub(crate) fn switch(&mut self) {
+        self.player = self.player.other();
+    }
+
+    /// Places `mark` at board\[row]\[cell] for the current player, then hands the turn to the other player.
+    /// The mark has to be the player's own, but either can be placed in wild games.
+    /// <br><br>
+    /// Nothing changes if the cell is off the board, taken or a hole, if the mark isn't one the player can place,
+    /// or if the game is already over.
+    /// In rolling games a player who already has [`ROLLING_MARKS`] marks down loses their oldest one first.
+    pub fn make_move(&mut self, row: usize, cell: usize, mark: CellState) -> Result<(), &'static str> {
+        self.make_timed_move(row, cell, mark, Duration::ZERO)
+    }
+
+    /// The same as [`Game::make_move`], also recording that the player spent `took` deciding on it.
+    pub fn make_timed_move(&mut self, row: usize, cell: usize, mark: CellState, took: Duration) -> Result<(), &'static str> {
+        if self.outcome.is_some() {
+            return Err("The game is already over!");
+        }
+
+        match mark {
+            CellState::Cross | CellState::Nought if self.rules.wild || mark == self.player.to_cell() => {}
+            CellState::Cross | CellState::Nought => return Err("Players can only place their own mark!"),
+            CellState::Empty | CellState::Blocked => return Err("Only a cross or a nought can be placed!")
+        }
+
+        match self.board.iter().nth(row).and_then(|columns| columns.get(cell)) {
+            None => return Err("Cell is not on the board!"),
+            Some(CellState::Empty) => {}
+            Some(CellState::Blocked) => return Err("Cell is blocked!"),
+            Some(_) => return Err("Cell has already been played!")
+        }
+
+        self.place(row, cell, mark, took);
+
+        Ok(())
+    }
+
+    /// Plays a move [`Game::make_timed_move`] has already checked, returning the mark it rolled off the board, if any,
+    /// as (row, column, mark) for [`Game::unplay`].
+    pub(crate) fn place(&mut self, row: usize, cell: usize, mark: CellState, took: Duration) -> Option<(usize, usize, CellState)> {
+        let placed = match self.player {
+            Player::Crosses => &mut self.crosses_placed,
+            Player::Noughts => &mut self.noughts_placed
+        };
+
+        let mut rolled = None;
+
+        if self.rules.rolling && placed.len() == ROLLING_MARKS {
+            if let Some((old_row, old_cell)) = placed.pop_front() {
+                rolled = Some((old_row, old_cell, self.board[old_row][old_cell]));
+                self.board[old_row][old_cell] = CellState::Empty;
+            }
+        }
+
+        placed.push_back((row, cell));
+        self.board[row][cell] = mark;
+        self.history.push(Move::Place(self.player, row, cell, mark));
+        self.times.push((coord_to_index(row, cell), took));
+        self.update_outcome();
+        self.switch();
+
+        rolled
+    }
+
+    /// Takes back the last move [`Game::place`] played, putting back the mark it rolled off the board.
+    /// <br><br>
+    /// The computer players try moves out this way instead of on copies of the game, which would be much slower.
+    pub(crate) fn unplay(&mut self, rolled: Option<(usize, usize, CellState)>) {
+        self.switch();
+        self.history.pop();
+        self.times.pop();
+
+        let placed = match self.player {
+            Player::Crosses => &mut self.crosses_placed,
+            Player::Noughts => &mut self.noughts_placed
+        };
+
+        if let Some((row, cell)) = placed.pop_back() {
+            self.board[row][cell] = CellState::Empty;
+        }
+
+        if let Some((row, cell, mark)) = rolled {
+            self.board[row][cell] = mark;
+            placed.push_front((row, cell));
+        }
+
+        // Moves are only played in games that are still going
+        self.outcome = None;
+    }
+
+    /// A copy of the game with the current player's mark placed in the cell with the given 1-based index
+    /// and the turn passed to the other player, leaving this game as it was.
+    pub fn clone_with_move(&self, index: usize) -> Result<Game, GameError> {
+        let (row, cell) = index_to_coord(index).ok_or(GameError::OffBoard(index))?;
+
+        match self.board[row][cell] {
+            CellState::Empty => {}
+            CellState::Blocked => return Err(GameError::Blocked(index)),
+            _ => return Err(GameError::Occupied(index))
+        }
+
+        let mut next = self.clone();
+
+        next.make_move(row, cell, self.player.to_cell()).expect("the cell was checked to be empty");
+
+        Ok(next)
+    }
+
+    /// The 1-based cell of every mark placed so far, with how long the player took to choose it.
+    /// <br><br>
+    /// Moves made through [`Game::make_move`], such as in simulated games, are recorded as taking no time.
+    pub fn move_times(&self) -> &[(usize, Duration)] {
+        &self.times
+    }
+
+    /// The cell whose mark `player` would lose on their next move in a rolling game, as (row, column).
+    pub fn next_to_roll(&self, player: Player) -> Option<(usize, usize)> {
+        let placed = match player {
+            Player::Crosses => &self.crosses_placed,
+            Player::Noughts => &self.noughts_placed
+        };
+
+        match (self.rules.rolling, placed.len()) {
+            (true, ROLLING_MARKS) => placed.front().copied(),
+            _ => None
+        }
+    }
+
+    /// The player who placed the most recent mark.
+    pub fn last_placed_by(&self) -> Option<Player> {
+        self.history.iter().rev().find_map(|turn| match turn {
+            Move::Place(player, _, _, _) => Some(*player),
+            Move::Pass => None
+        })
+    }
+
+    /// The 1-based indices of the empty cells, in order.
+    /// <br><br>
+    /// Unlike [`crate::empty_cells`] this doesn't build a `Vec`, and it doesn't check whether the game is already over.
+    pub fn legal_moves(&self) -> impl Iterator<Item = usize> + '_ {
+        self.board
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter(|(_, cell)| cell == &&CellState::Empty)
+            .map(|(i, _)| i + 1)
+    }
+
+    /// The marks the current player can place, their own first and then, in wild games, the other player's.
+    pub fn marks(&self) -> &'static [CellState] {
+        match (self.player, self.rules.wild) {
+            (Player::Crosses, false) => &[CellState::Cross],
+            (Player::Crosses, true) => &[CellState::Cross, CellState::Nought],
+            (Player::Noughts, false) => &[CellState::Nought],
+            (Player::Noughts, true) => &[CellState::Nought, CellState::Cross]
+        }
+    }
+
+    /// Every move the current player can make as a 1-based cell and the mark to place there,
+    /// in the order of [`Game::legal_moves`] and then [`Game::marks`].
+    pub fn placements(&self) -> impl Iterator<Item = (usize, CellState)> + '_ {
+        self.legal_moves().flat_map(|index| self.marks().iter().map(move |mark| (index, *mark)))
+    }
+
+    /// How the game has ended under its rules, or `None` while it's still going.
+    /// <br><br>
+    /// A completed line wins, and a full board, both players passing in a row or reaching the move limit is a draw.
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        self.outcome
+    }
+
+    /// The player who completed a line, if the game has been won.
+    /// <br><br>
+    /// In wild games that's whoever placed the last mark, whichever mark it was.
+    pub fn winner(&self) -> Option<Player> {
+        match self.outcome {
+            Some(GameOutcome::Win(player)) => Some(player),
+            _ => None
+        }
+    }
+
+    /// Works out [`Game::outcome`] for the position just reached, from one where the game was still going.
+    fn update_outcome(&mut self) {
+        let out_of_moves = self.rules.move_limit.is_some_and(|limit| self.history.len() >= limit);
+
+        // A new line has to run through the mark just placed, since marks coming off the board can't complete one
+        let line = match self.history.last() {
+            Some(Move::Place(player, row, cell, _)) => match check_win_at(&self.board, coord_to_index(*row, *cell), &self.rules) {
+                // Both players share both marks, so the line belongs to whoever completed it
+                Some(_) if self.rules.wild => Some(*player),
+                owner => owner
+            },
+            _ => None
+        };
+
+        self.outcome = match line {
+            Some(player) => Some(GameOutcome::Win(player)),
+            None if out_of_moves || is_full(&self.board) || self.history.ends_with(&[Move::Pass, Move::Pass]) => Some(GameOutcome::Draw),
+            None => None
+        };
+    }
+
+    /// Gives the turn to the other player without placing a mark.
+    pub fn pass(&mut self) {
+        self.history.push(Move::Pass);
+        self.switch();
+        self.update_outcome();
+    }
+}
+
+/// How a bare board has ended under `rules`, or `None` if there's still a move to make.
+/// <br><br>
+/// A completed line wins for the player whose marks fill it, counting only the lines the rules have and never one through a hole,
+/// and a full board is a draw. A board can't say who placed its marks, how many moves it took or which marks roll off next,
+/// so this knows nothing of wild marks, the move limit, passes or rolling.
+/// <br><br>
+/// Games in progress are decided by [`Game::outcome`] instead, which follows every variant, and that's also what the computer players search by.
+/// This is for boards that don't come from a game, such as puzzles and positions read in.
+pub fn check_win_variant(board: &Board, rules: &Rules) -> Option<GameOutcome> {
+    if let Some(win) = check_win(board, rules) {
+        Some(GameOutcome::Win(win.player))
+    } else if is_full(board) {
+        Some(GameOutcome::Draw)
+    } else {
+        None
+    }
+}
+
+/// Which cells can be played next, with cell `i` at `map[i - 1]`, for front ends that enable a button for each cell.
+/// <br><br>
+/// Every variant only allows empty cells, including rolling games where the cell about to be cleared is still taken.
+/// Nothing can be played once the game is over.
+pub fn legality_map(game: &Game) -> [bool; 9] {
+    let mut map = [false; 9];
+
+    if game.outcome().is_none() {
+        for index in game.legal_moves() {
+            map[index - 1] = true;
+        }
+    }
+
+    map
+}
+
+impl Default for Game {
+    fn default() -> Game {
+        Game::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{board_from_line, board_to_line, completed_lines};
+    use crate::rng::Rng;
+
+    /// Plays each cell in turn with the mover's own mark.
+    fn play(game: &mut Game, cells: &[usize]) {
+        for index in cells {
+            let (row, cell) = index_to_coord(*index).unwrap();
+
+            game.make_move(row, cell, game.current_player().to_cell()).unwrap();
+        }
+    }
+
+    /// The 1-based cells a [`legality_map`] allows.
+    fn playable(game: &Game) -> Vec<usize> {
+        (1..=9).filter(|index| legality_map(game)[index - 1]).collect()
+    }
+
+    #[test]
+    fn random_games_keep_to_the_rules() {
+        let mut holes = [false; 9];
+        holes[4] = true;
+
+        let variants = [Rules::standard(), Rules { diagonals: false, ..Rules::standard() }, Rules { holes, ..Rules::standard() }];
+
+        for rules in variants {
+            let cells = rules.holes.iter().filter(|hole| !**hole).count();
+
+            for seed in 0..500 {
+                let mut rng = Rng::new(seed);
+                let mut game = Game::with_rules(rules);
+                let mut played = Vec::new();
+
+                while game.outcome().is_none() {
+                    let free: Vec<usize> = game.legal_moves().collect();
+                    let index = free[rng.below(free.len())];
+                    let (row, cell) = index_to_coord(index).unwrap();
+
+                    played.push(index);
+                    game.make_move(row, cell, game.current_player().to_cell()).unwrap();
+
+                    // Printed with every failure, so the game can be played through again by hand
+                    let transcript = format!("seed {} with {:?}, moves {:?}", seed, rules, played);
+
+                    assert!(game.history().len() <= cells, "more moves than cells: {}", transcript);
+
+                    if let Some(player) = game.winner() {
+                        assert!(game.history().len() >= 5, "won before either side had three marks: {}", transcript);
+                        assert!(completed_lines(game.board(), &rules).iter().any(|(_, owner)| *owner == player), "won without a line: {}", transcript);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn illegal_moves_leave_the_game_as_it_was() {
+        let mut game = Game::new();
+        play(&mut game, &[5]);
+
+        assert_eq!(game.make_move(3, 0, CellState::Nought), Err("Cell is not on the board!"));
+        assert_eq!(game.make_move(0, 7, CellState::Nought), Err("Cell is not on the board!"));
+        assert_eq!(game.make_move(1, 1, CellState::Nought), Err("Cell has already been played!"));
+        assert_eq!(game.make_move(0, 0, CellState::Cross), Err("Players can only place their own mark!"));
+        assert_eq!(game.make_move(0, 0, CellState::Empty), Err("Only a cross or a nought can be placed!"));
+        assert_eq!(game.make_move(0, 0, CellState::Blocked), Err("Only a cross or a nought can be placed!"));
+
+        assert_eq!(board_to_line(game.board()), "...|.X.|...");
+        assert_eq!(game.current_player(), &Player::Noughts);
+        assert_eq!(game.history().len(), 1);
+
+        play(&mut game, &[1, 2, 4, 8]);
+        assert_eq!(game.outcome(), Some(GameOutcome::Win(Player::Crosses)));
+        assert_eq!(game.make_move(2, 2, CellState::Nought), Err("The game is already over!"));
+    }
+
+    #[test]
+    fn legal_moves_are_the_empty_cells_in_order() {
+        let mut game = Game::with_rules(Rules { holes: [false, true, false, false, false, false, false, false, false], ..Rules::standard() });
+
+        game.make_move(1, 1, CellState::Cross).unwrap();
+        assert_eq!(game.legal_moves().collect::<Vec<_>>(), [1, 3, 4, 6, 7, 8, 9]);
+
+        game.board = board_from_line("XOX|XOO|OXX").unwrap();
+        assert_eq!(game.legal_moves().next(), None);
+    }
+
+    #[test]
+    fn clone_with_move_leaves_the_game_as_it_was() {
+        let mut game = Game::new();
+
+        game.make_move(1, 1, CellState::Cross).unwrap();
+
+        let next = game.clone_with_move(1).unwrap();
+
+        assert_eq!(board_to_line(next.board()), "O..|.X.|...");
+        assert_eq!(next.current_player(), &Player::Crosses);
+        assert_eq!(next.history().len(), 2);
+
+        assert_eq!(board_to_line(game.board()), "...|.X.|...");
+        assert_eq!(game.current_player(), &Player::Noughts);
+        assert_eq!(game.history().len(), 1);
+
+        assert_eq!(game.clone_with_move(5).err(), Some(GameError::Occupied(5)));
+        assert_eq!(game.clone_with_move(10).err(), Some(GameError::OffBoard(10)));
+    }
+
+    #[test]
+    fn legality_map_follows_each_variant() {
+        let mut game = Game::new();
+        play(&mut game, &[5, 1]);
+        assert_eq!(playable(&game), [2, 3, 4, 6, 7, 8, 9]);
+
+        let mut game = Game::with_rules(Rules { diagonals: false, ..Rules::standard() });
+        play(&mut game, &[5, 1]);
+        assert_eq!(playable(&game), [2, 3, 4, 6, 7, 8, 9]);
+
+        let mut holes = [false; 9];
+        holes[2] = true;
+        holes[6] = true;
+
+        let mut game = Game::with_rules(Rules { holes, ..Rules::standard() });
+        play(&mut game, &[5]);
+        assert_eq!(playable(&game), [1, 2, 4, 6, 8, 9]);
+
+        // Crosses' mark in cell 1 is about to roll off, but it's still there until their next move
+        let mut game = Game::with_rules(Rules { rolling: true, ..Rules::standard() });
+        play(&mut game, &[1, 2, 4, 5, 8, 3]);
+        assert_eq!(game.next_to_roll(Player::Crosses), Some((0, 0)));
+        assert_eq!(playable(&game), [6, 7, 9]);
+
+        // Either mark can go in, but still only in an empty cell
+        let mut game = Game::with_rules(Rules { wild: true, ..Rules::standard() });
+        game.make_move(0, 0, CellState::Nought).unwrap();
+        assert_eq!(playable(&game), [2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn legality_map_is_empty_once_the_game_is_over() {
+        let mut game = Game::new();
+        play(&mut game, &[1, 4, 2, 5, 3]);
+        assert_eq!(playable(&game), []);
+
+        let mut game = Game::with_rules(Rules { move_limit: Some(2), ..Rules::standard() });
+        play(&mut game, &[1, 5]);
+        assert_eq!(game.outcome(), Some(GameOutcome::Draw));
+        assert_eq!(playable(&game), []);
+    }
+
+    #[test]
+    fn the_cached_outcome_follows_every_move() {
+        let mut game = Game::new();
+
+        for index in [1, 4, 2, 5] {
+            play(&mut game, &[index]);
+            assert_eq!(game.outcome(), check_win_variant(game.board(), game.rules()));
+            assert_eq!(game.outcome(), None);
+        }
+
+        // The copy wins, but the game it came from is still going
+        let won = game.clone_with_move(3).unwrap();
+
+        assert_eq!(won.outcome(), Some(GameOutcome::Win(Player::Crosses)));
+        assert_eq!(won.winner(), Some(Player::Crosses));
+        assert_eq!(game.outcome(), None);
+        assert_eq!(game.winner(), None);
+
+        game.pass();
+        assert_eq!(game.outcome(), None);
+        game.pass();
+        assert_eq!(game.outcome(), Some(GameOutcome::Draw));
+    }
+
+    #[test]
+    fn the_cached_outcome_follows_rolling_the_limit_passes_and_undo() {
+        let rolling = Rules { rolling: true, ..Rules::standard() };
+        let limited = Rules { move_limit: Some(7), ..Rules::standard() };
+
+        // After 1 4 2 5 7 8, crosses play 3 to complete the top row, except in rolling games where their mark in 1
+        // comes off first. Under the limit their seventh move, here into 9, is the last one.
+        let cases = [
+            (Rules::standard(), 3, Some(GameOutcome::Win(Player::Crosses)), None),
+            (rolling, 3, None, Some((0, 0, CellState::Cross))),
+            (limited, 9, Some(GameOutcome::Draw), None)
+        ];
+
+        for (rules, index, outcome, rolled) in cases {
+            let mut game = Game::with_rules(rules);
+            play(&mut game, &[1, 4, 2, 5, 7, 8]);
+
+            let before = game.clone();
+            let (row, cell) = index_to_coord(index).unwrap();
+
+            let removed = game.place(row, cell, CellState::Cross, Duration::ZERO);
+
+            assert_eq!(removed, rolled, "the mark rolled off with {:?}", rules);
+            assert_eq!(game.outcome(), outcome, "after the move with {:?}", rules);
+
+            // Taking the move back puts the game back as it was, the cached outcome included
+            game.unplay(removed);
+
+            assert_eq!(board_to_line(game.board()), board_to_line(before.board()), "board after undo with {:?}", rules);
+            assert!(game.history() == before.history(), "history after undo with {:?}", rules);
+            assert_eq!(game.current_player(), before.current_player(), "player after undo with {:?}", rules);
+            assert_eq!(game.next_to_roll(Player::Crosses), before.next_to_roll(Player::Crosses), "next to roll after undo with {:?}", rules);
+            assert_eq!(game.outcome(), None, "outcome after undo with {:?}", rules);
+
+            // The game carries on the same way after the undo
+            game.place(row, cell, CellState::Cross, Duration::ZERO);
+            assert_eq!(game.outcome(), outcome, "after playing the move again with {:?}", rules);
+        }
+
+        // Only two passes in a row draw, a move in between starts the count again
+        let mut game = Game::new();
+
+        game.pass();
+        play(&mut game, &[5]);
+        game.pass();
+        assert_eq!(game.outcome(), None);
+
+        game.pass();
+        assert_eq!(game.outcome(), Some(GameOutcome::Draw));
+    }
+
+    #[test]
+    fn check_win_variant_under_every_combination_of_rules() {
+        // A bare board only has its lines and holes to go on, the other variants need a game
+        for diagonals in [true, false] {
+            for hole in [true, false] {
+                let mut holes = [false; 9];
+                holes[8] = hole;
+
+                let rules = Rules { diagonals, holes, ..Rules::standard() };
+                let last = if hole { '#' } else { '.' };
+                let outcome = |line: &str| check_win_variant(&board_from_line(&line.replace('_', &last.to_string())).unwrap(), &rules);
+
+                assert_eq!(outcome("XXX|OO.|.._"), Some(GameOutcome::Win(Player::Crosses)), "row with {:?}", rules);
+                assert_eq!(outcome("O.X|OX.|X._"), diagonals.then_some(GameOutcome::Win(Player::Crosses)), "diagonal with {:?}", rules);
+                assert_eq!(outcome("X..|.O.|.._"), None, "open board with {:?}", rules);
+
+                // With the hole the last cell can't be played, so the board is already full
+                let full = if hole { "XOX|XOO|OX#" } else { "XOX|XOO|OXX" };
+                assert_eq!(outcome(full), Some(GameOutcome::Draw), "full board with {:?}", rules);
+            }
+        }
+    }
+
+    #[test]
+    fn outcome_under_every_combination_of_rules() {
+        for diagonals in [true, false] {
+            for rolling in [true, false] {
+                for wild in [true, false] {
+                    for hole in [true, false] {
+                        let mut holes = [false; 9];
+                        holes[8] = hole;
+
+                        let rules = Rules { diagonals, rolling, holes, wild, move_limit: None };
+
+                        // Cells played in turn, with `x` or `o` after one that takes the other player's mark
+                        let outcome = |moves: &str| -> Result<Option<GameOutcome>, &'static str> {
+                            let mut game = Game::with_rules(rules);
+
+                            for word in moves.split(' ') {
+                                let (index, mark) = match word.split_at(1) {
+                                    (index, "x") => (index, CellState::Cross),
+                                    (index, "o") => (index, CellState::Nought),
+                                    (index, _) => (index, game.current_player().to_cell())
+                                };
+                                let (row, cell) = index_to_coord(index.parse().unwrap()).unwrap();
+
+                                game.make_move(row, cell, mark)?;
+                            }
+
+                            Ok(game.outcome())
+                        };
+
+                        let win = |player: Player| Ok(Some(GameOutcome::Win(player)));
+
+                        assert_eq!(outcome("1 4 2 5 3"), win(Player::Crosses), "row with {:?}", rules);
+                        assert_eq!(outcome("3 1 5 2 7"), if diagonals { win(Player::Crosses) } else { Ok(None) }, "diagonal with {:?}", rules);
+
+                        // Crosses' fourth mark takes the one in cell 1 off, breaking up the top row
+                        let fourth = if rolling { Ok(None) } else { win(Player::Crosses) };
+                        assert_eq!(outcome("1 4 2 5 7 8 3"), fourth, "fourth mark with {:?}", rules);
+
+                        // Noughts fill the top row with crosses, which is their own win in wild games and not allowed otherwise
+                        let wild_row = if wild { win(Player::Noughts) } else { Err("Players can only place their own mark!") };
+                        assert_eq!(outcome("1 2x 4 3x"), wild_row, "another player's line with {:?}", rules);
+
+                        // Rolling games never fill up, and a board that does fills a cell sooner with the hole
+                        let filled = if hole { "1 2 4 5 3 6 8 7" } else { "1 2 4 5 3 6 8 7 9" };
+                        let full = if rolling { Ok(None) } else { Ok(Some(GameOutcome::Draw)) };
+                        assert_eq!(outcome(filled), full, "full board with {:?}", rules);
+
+                        let limited = Rules { move_limit: Some(2), ..rules };
+                        let mut game = Game::with_rules(limited);
+                        play(&mut game, &[1, 5]);
+                        assert_eq!(game.outcome(), Some(GameOutcome::Draw), "move limit with {:?}", limited);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wild_wins_go_to_whoever_completed_the_line() {
+        let mut game = Game::with_rules(Rules { wild: true, ..Rules::standard() });
+
+        for (row, cell) in [(0, 0), (1, 1), (0, 1)] {
+            game.make_move(row, cell, CellState::Cross).unwrap();
+        }
+
+        game.make_move(0, 2, CellState::Cross).unwrap();
+
+        assert_eq!(check_win_variant(game.board(), game.rules()), Some(GameOutcome::Win(Player::Crosses)));
+        assert_eq!(game.outcome(), Some(GameOutcome::Win(Player::Noughts)));
+    }
+}
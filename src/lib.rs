@@ -0,0 +1,13 @@
+//! The rules of tic tac toe, along with the strategies that can play it.
+
+mod analysis;
+mod board;
+mod game;
+mod rng;
+mod strategy;
+
+pub use analysis::{analyze_game, canonical, count_games, forced_move, is_dead_draw, is_decided, random_midgame, random_opening, rate_move, tablebase, to_move, MoveQuality, TreeSize};
+pub use board::{board_diff, board_from_line, board_to_line, check_win, check_win_at, completed_lines, coord_to_index, creates_fork, describe_win, empty_cells, index_to_coord, is_full, line_name, lines, reflect_horizontal, rotate90, symmetries, winning_moves, Board, CellState, GameError, Line, Player, Rules, WinInfo};
+pub use game::{check_win_variant, legality_map, Game, GameOutcome, Move, ROLLING_MARKS};
+pub use rng::Rng;
+pub use strategy::{evaluate, explain_move, mirror_move, next_difficulty, play_game, play_game_fast_draws, principal_variation, run_game_with_moves, Action, BlockingAi, Difficulty, MinimaxAi, MirrorAi, RandomAi, ScriptedMoves, Seating, Strategy, WeightedAi, ROLLING_HORIZON};
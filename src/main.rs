@@ -1,283 +1,4901 @@
-use console::{Key, Term};
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-/// The board of a tic tac toe game.
-/// <br><br>
-/// Below is a visual of the cell indices (formatted board\[row]\[column]):
-/// ```
-/// [1] [2] [3] < -- 0
-/// [4] [5] [6] < -- 1
-/// [7] [8] [9] < -- 2
-///  ^   ^   ^
-///  |   |   |
-///  0   1   2
-/// ```
-type Board = [[CellState; 3]; 3];
+use console::{style, Key, Term};
+use unicode_width::UnicodeWidthStr;
+
+use tic_tac_toe::{analyze_game, board_diff, board_from_line, board_to_line, check_win, check_win_variant, completed_lines, coord_to_index, count_games, creates_fork, evaluate, explain_move, forced_move, index_to_coord, is_dead_draw, is_decided, is_full, line_name, mirror_move, next_difficulty, play_game, play_game_fast_draws, principal_variation, random_midgame, random_opening, rate_move, tablebase, winning_moves, Action, Board, CellState, Difficulty, Game, GameOutcome, Line, MinimaxAi, MirrorAi, Move, MoveQuality, Player, Rng, Rules, Seating, Strategy, WeightedAi, WinInfo};
+
+/// The exit code for anything going wrong, kept apart from the codes [`exit_code`] gives finished games.
+const EXIT_ERROR: i32 = 4;
+
+/// The exit code for a finished game: 1 when crosses won, 2 when noughts won and 3 for a draw, see [`main`].
+fn exit_code(outcome: GameOutcome) -> i32 {
+    match outcome {
+        GameOutcome::Win(Player::Crosses) => 1,
+        GameOutcome::Win(Player::Noughts) => 2,
+        GameOutcome::Draw => 3
+    }
+}
 
-enum Player {
-    Noughts,
-    Crosses,
+/// Who chooses the moves for one side of the board.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Controller {
+    #[default]
+    Human,
+    Computer(Difficulty),
+    /// Copies the opponent's moves through the centre, to show why that doesn't work.
+    Mirror,
+    /// Favours good moves without always finding them, at the given temperature, see [`WeightedAi`].
+    Weighted(f64),
+    /// Runs the `--external-ai` program, see [`ExternalAi`].
+    External,
 }
 
-impl Player {
-    fn to_letter(&self) -> char {
+impl Controller {
+    fn parse(name: &str) -> Result<Controller, String> {
+        match name {
+            "human" => Ok(Controller::Human),
+            "easy" | "random" => Ok(Controller::Computer(Difficulty::Easy)),
+            "medium" => Ok(Controller::Computer(Difficulty::Medium)),
+            "hard" | "minimax" => Ok(Controller::Computer(Difficulty::Hard)),
+            "mirror" => Ok(Controller::Mirror),
+            _ => Err(format!("Unknown player type: {} (expected human, easy, medium, hard, minimax, random or mirror)", name))
+        }
+    }
+
+    fn to_name(self) -> String {
         match self {
-            Player::Noughts => 'O',
-            Player::Crosses => 'X'
+            Controller::Human => String::from("Human"),
+            Controller::Computer(level) => String::from(level.to_name()),
+            Controller::Mirror => String::from("Mirror"),
+            Controller::Weighted(temperature) => format!("Weighted ({})", temperature),
+            Controller::External => String::from("External")
         }
     }
 
-    fn to_number(&self) -> i32 {
+    /// The computer player for this side, drawing any random moves from `rng`, or `None` for a human.
+    fn computer(self, rng: Rng) -> Option<Box<dyn Strategy>> {
         match self {
-            Player::Noughts => 2,
-            Player::Crosses => 1
+            Controller::Human => None,
+            Controller::Computer(level) => Some(level.strategy(rng)),
+            Controller::Mirror => Some(Box::new(MirrorAi)),
+            Controller::Weighted(temperature) => Some(Box::new(WeightedAi::new(temperature, rng))),
+            // The program's command is in the options, which self-play never gets this far without
+            Controller::External => None
         }
     }
+
+    /// The player for this side in a game on the console, with `stopped` for people and programs to say why the session has to end.
+    fn strategy(self, options: &Options, clock: &Option<Rc<RefCell<ChessClock>>>, stopped: &Stopped) -> Result<Box<dyn Strategy>, String> {
+        Ok(match self {
+            Controller::Human if options.format == Format::Json => Box::new(LineInput { options: options.clone(), stopped: stopped.clone() }),
+            Controller::Human => Box::new(HumanConsole { options: options.clone(), clock: clock.clone(), cursor: 5, stopped: stopped.clone() }),
+            Controller::Computer(level) => level.strategy(Rng::from_time()),
+            Controller::Mirror => Box::new(MirrorAi),
+            // With --seed the same game can be played against it again
+            Controller::Weighted(temperature) => Box::new(WeightedAi::new(temperature, options.seed.map_or_else(Rng::from_time, Rng::new))),
+            Controller::External => {
+                let command = options.external_ai.as_deref().unwrap_or_default();
+
+                match ExternalAi::spawn(command, stopped.clone()) {
+                    Ok(ai) => Box::new(ai),
+                    Err(error) => return Err(format!("Could not start the external AI `{}` ({}).", command, error))
+                }
+            }
+        })
+    }
 }
 
-#[derive(PartialEq)]
-enum CellState {
-    Empty,
-    Nought,
-    Cross,
+/// How the game is shown to the player.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Format {
+    /// The board drawn for people to read.
+    #[default]
+    Pretty,
+    /// One JSON object per line, for other programs to read.
+    Json,
 }
 
-impl CellState {
-    fn to_player(&self) -> Result<Player, &'static str> {
-        match self {
-            CellState::Empty => Err("Cell has not been played!"),
-            CellState::Nought => Ok(Player::Noughts),
-            CellState::Cross => Ok(Player::Crosses)
+/// What happens when a player makes a move the rules don't allow, such as a bot playing in a taken cell.
+/// <br><br>
+/// Answers that aren't a move at all, or bots that crash or hang, still end the program whatever this says.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum IllegalMovePolicy {
+    /// Stop the program with an error.
+    #[default]
+    Abort,
+    /// Count it as a pass, so two in a row draw the game.
+    Skip,
+    /// Lose the game.
+    Forfeit,
+}
+
+impl IllegalMovePolicy {
+    fn parse(name: &str) -> Result<IllegalMovePolicy, String> {
+        match name {
+            "abort" => Ok(IllegalMovePolicy::Abort),
+            "skip" => Ok(IllegalMovePolicy::Skip),
+            "forfeit" => Ok(IllegalMovePolicy::Forfeit),
+            _ => Err(format!("Unknown illegal move policy: {} (expected abort, skip or forfeit)", name))
         }
     }
 }
 
-struct Game {
-    board: Board,
-    player: Player,
+/// How a drawn game counts in an `--ai-vs-ai-tournament`.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum DrawPolicy {
+    /// Play the game again, up to [`MAX_REPLAYS`] times, after which the draw stands.
+    Replay,
+    /// Give each side half a point.
+    HalfPoint,
+    /// Leave draws out of the points, so only wins count.
+    #[default]
+    NoCount,
 }
 
-impl Game {
-    fn switch(&mut self) {
-        match self.player {
-            Player::Noughts => self.player = Player::Crosses,
-            Player::Crosses => self.player = Player::Noughts
+impl DrawPolicy {
+    fn parse(name: &str) -> Result<DrawPolicy, String> {
+        match name {
+            "replay" => Ok(DrawPolicy::Replay),
+            "half-point" => Ok(DrawPolicy::HalfPoint),
+            "no-count" => Ok(DrawPolicy::NoCount),
+            _ => Err(format!("Unknown draw policy: {} (expected replay, half-point or no-count)", name))
+        }
+    }
+
+    /// The points each side gets for a draw.
+    fn draw_points(self) -> f64 {
+        match self {
+            DrawPolicy::HalfPoint => 0.5,
+            DrawPolicy::Replay | DrawPolicy::NoCount => 0.0
+        }
+    }
+
+    /// What the policy does with draws, to show beside the points.
+    fn describe(self) -> &'static str {
+        match self {
+            DrawPolicy::Replay => "draws replayed, any left count nothing",
+            DrawPolicy::HalfPoint => "half a point each for a draw",
+            DrawPolicy::NoCount => "draws count nothing"
         }
     }
 }
 
-fn get_cell(state: &CellState, cell: &mut usize) -> String {
-    *cell += 1;
+/// How many times `--draw-policy replay` plays a drawn game again before letting the draw stand,
+/// since computers that play perfectly draw every time.
+const MAX_REPLAYS: usize = 10;
 
-    return match state {
-        CellState::Empty => { format!("[{}]", cell) }
-        CellState::Nought => { String::from("[O]") }
-        CellState::Cross => { String::from("[X]") }
-    };
+/// How the winning line is animated before the final board, which only happens when `--delay` is set.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum WinStyle {
+    /// Turn the winning cells on and off.
+    Blink,
+    /// Light the winning cells one at a time from left to right.
+    #[default]
+    Sweep,
+    /// Fade the winning cells up and down.
+    Pulse,
+    /// Leave the line highlighted without animating it.
+    None,
+}
+
+impl WinStyle {
+    fn parse(name: &str) -> Result<WinStyle, String> {
+        match name {
+            "blink" => Ok(WinStyle::Blink),
+            "sweep" => Ok(WinStyle::Sweep),
+            "pulse" => Ok(WinStyle::Pulse),
+            "none" => Ok(WinStyle::None),
+            _ => Err(format!("Unknown win style: {} (expected blink, sweep, pulse or none)", name))
+        }
+    }
 }
 
-fn draw_board(board: &Board) {
-    let mut i = 0;
+/// How brightly [`draw_board_glowing`] picks out its cells.
+#[derive(Clone, Copy, PartialEq)]
+enum Glow {
+    Dim,
+    Plain,
+    Bright,
+}
 
-    for row in board {
-        println!(
-            "{} {} {}",
-            get_cell(&row[0], &mut i),
-            get_cell(&row[1], &mut i),
-            get_cell(&row[2], &mut i)
-        );
+/// The symbols the board is drawn with.
+#[derive(Clone)]
+struct Theme {
+    cross: String,
+    nought: String,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme { cross: String::from("X"), nought: String::from("O") }
     }
 }
 
-fn get_input(game: &mut Game, draw: bool) -> bool {
-    let term = Term::stdout();
+impl Theme {
+    fn parse(name: &str) -> Result<Theme, String> {
+        match name {
+            "ascii" => Ok(Theme::default()),
+            "emoji" => Ok(Theme { cross: String::from("❌"), nought: String::from("⭕") }),
+            _ => Err(format!("Unknown theme: {} (expected ascii or emoji)", name))
+        }
+    }
 
-    if draw {
-        println!("Please type a number to place an {letter}\nPlayer {number} ({letter}): ", letter = game.player.to_letter(), number = game.player.to_number());
+    /// How many columns of the terminal the inside of every cell takes up, so the grid lines up.
+    fn cell_width(&self) -> usize {
+        UnicodeWidthStr::width(self.cross.as_str()).max(UnicodeWidthStr::width(self.nought.as_str())).max(1)
     }
+}
 
-    match term.read_key() {
-        Ok(result) => {
-            match result {
-                Key::Char(char) if char.is_digit(10) => {
-                    match char.to_string().parse::<f32>() {
-                        Ok(digit) => {
-                            let mut index: Option<(usize, usize)> = None;
-
-                            match digit.trunc() as i32 {
-                                1 => index = Some((0, 0)),
-                                2 => index = Some((0, 1)),
-                                3 => index = Some((0, 2)),
-                                4 => index = Some((1, 0)),
-                                5 => index = Some((1, 1)),
-                                6 => index = Some((1, 2)),
-                                7 => index = Some((2, 0)),
-                                8 => index = Some((2, 1)),
-                                9 => index = Some((2, 2)),
-                                _ => {}
-                            }
+/// How the empty cells are numbered, which is also the key that plays in each of them.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Numbering {
+    /// 1 2 3 along the top row, in reading order.
+    #[default]
+    Reading,
+    /// 7 8 9 along the top row, laid out like a keyboard's number pad.
+    Numpad,
+}
 
-                            match index {
-                                Some(i) => {
-                                    let row = i.0;
-                                    let cell = i.1;
-
-                                    match game.board[row][cell] {
-                                        CellState::Empty => {
-                                            match game.player {
-                                                Player::Noughts => game.board[row][cell] = CellState::Nought,
-                                                Player::Crosses => game.board[row][cell] = CellState::Cross
-                                            }
-                                            return true;
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-                _ => {}
-            }
+impl Numbering {
+    fn parse(name: &str) -> Result<Numbering, String> {
+        match name {
+            "reading" => Ok(Numbering::Reading),
+            "numpad" => Ok(Numbering::Numpad),
+            _ => Err(format!("Unknown numbering: {} (expected reading or numpad)", name))
+        }
+    }
+
+    /// The number shown for the cell with the given 1-based index.
+    /// <br><br>
+    /// The number pad only swaps the top and bottom rows, which undoes itself, so this also turns a number typed in back into its cell.
+    /// Anything off the board is left as it is.
+    fn label(self, index: usize) -> usize {
+        match (self, index_to_coord(index)) {
+            (Numbering::Numpad, Some((row, col))) => coord_to_index(2 - row, col),
+            _ => index
         }
-        _ => {}
     }
+}
 
-    false
+/// Options passed on the command line.
+#[derive(Clone, Default)]
+struct Options {
+    /// Hide the board during play so moves have to be called from memory.
+    blind: bool,
+    /// Clear the saved scoreboard before playing.
+    reset_scores: bool,
+    /// Let players pass their turn instead of placing a mark.
+    allow_pass: bool,
+    /// Draw the board with its columns reversed, for players sitting across the table.
+    mirror: bool,
+    crosses: Controller,
+    noughts: Controller,
+    /// List every completed line below the board, for checking the win detection.
+    debug_lines: bool,
+    /// Show how many cells would win the game for each player.
+    threats: bool,
+    /// Let the human move a cursor over the board and see the lines through its cell, see [`teach_overlay`].
+    teach: bool,
+    /// Each player's total thinking time and the time added after each of their moves.
+    clock: Option<(Duration, Duration)>,
+    format: Format,
+    on_illegal: IllegalMovePolicy,
+    draw_policy: DrawPolicy,
+    /// Type cell numbers and confirm them with Enter, instead of playing on the first key press.
+    strict: bool,
+    /// Make the computer harder after the human wins and easier after they lose, offering rematches in between.
+    adaptive: bool,
+    /// Announce each computer move and wait for a key before playing it.
+    step_ai: bool,
+    /// Keep this file holding the current board, rewriting it after every move.
+    board_file: Option<PathBuf>,
+    /// Rate every move once the game is over.
+    analyze: bool,
+    /// Time between the frames of animations, which are off when this isn't set, and between games played back to back.
+    delay: Option<Duration>,
+    win_style: WinStyle,
+    theme: Theme,
+    /// Only ever print plain ASCII, with no colours, escape codes or Unicode symbols.
+    ascii_only: bool,
+    rules: Rules,
+    /// Ask before playing a move that hands the opponent a forced win.
+    assist: bool,
+    /// Refuse to play a move that hands the opponent a forced win, rather than asking like `assist`.
+    no_blunders: bool,
+    /// Start from this many random moves that still leave the game drawn.
+    random_open: Option<usize>,
+    /// Start from a random midgame made from this seed, which needn't be drawn.
+    seed_board: Option<u64>,
+    /// Seed for the random opening, so the same opening can be played again.
+    seed: Option<u64>,
+    /// Print a line such as "Game 12: O wins in 7 moves" after each game, in place of the boards when only computers play.
+    summary: bool,
+    /// Keep drawing the boards along with the summary lines.
+    verbose: bool,
+    /// List how many positions the hard computer searched for each move once the game is over.
+    profile_search: bool,
+    /// Hide the board between turns until the next of two humans is ready, throwing away keys typed in the meantime.
+    hotseat_pass: bool,
+    /// Ask who wins random positions instead of playing, see [`run_eval_quiz`].
+    eval_quiz: bool,
+    /// Show how the side to move forces a win from this position, with the human defending.
+    show_win: Option<Board>,
+    /// Read the position games start from off standard input, see [`read_position`].
+    position_stdin: bool,
+    /// The position read for `--position-stdin` once the flags have been parsed, which games start from in place of the empty board.
+    position: Option<Board>,
+    /// Play through the puzzles in this file instead of a game.
+    puzzles: Option<PathBuf>,
+    /// Never wait on the terminal, so any side left to a human is played by the random computer instead.
+    headless: bool,
+    /// Play this many computer games against each other and print how each opening cell turned out.
+    selfplay: Option<usize>,
+    /// Play this many games between the two computers, swapping who starts, and print how the `--crosses` one did.
+    tournament: Option<usize>,
+    /// End self-play games as draws once nobody can win, see [`play_game_fast_draws`].
+    fast_draws: bool,
+    /// Count the games and positions reachable from the start instead of playing.
+    count_games: bool,
+    /// Write the value of every reachable position to this file instead of playing.
+    dump_tablebase: Option<PathBuf>,
+    /// What to ask the human with on their turn, in place of "Player 1 (X): ", see [`fill_prompt`].
+    prompt: Option<String>,
+    /// Have the computer pick its moves at random, weighted towards better ones by this temperature.
+    temperature: Option<f64>,
+    /// Show who's winning with best play under the board, a learning aid that gives the game away.
+    evaluation_bar: bool,
+    /// Report what the terminal can do instead of playing, for working out why the board looks wrong.
+    doctor: bool,
+    /// Print the [`VARIANTS`] instead of playing.
+    list_variants: bool,
+    /// Write the game to this file once it's over, with each move's value and the reason for it.
+    annotate: Option<PathBuf>,
+    /// Add every finished game to this file as a [`GameRecord`].
+    record: Option<PathBuf>,
+    /// Show the games in this file of [`GameRecord`]s instead of playing.
+    replay: Option<PathBuf>,
+    /// Write the `--replay` games to this directory as one SVG picture per move instead of showing them, see [`export_replay`].
+    export_replay: Option<PathBuf>,
+    /// Check that the games in this file of [`GameRecord`]s are legal and finished, see [`verify_records`].
+    verify: Option<PathBuf>,
+    /// Leave out the line of keys under the board on a human's turn.
+    hide_footer: bool,
+    /// Write every move tried to standard error, see [`echo_move`].
+    echo_moves: bool,
+    /// Quit as soon as q is pressed, without asking first.
+    no_confirm_quit: bool,
+    /// The command for a program that plays noughts, see [`ExternalAi`].
+    external_ai: Option<String>,
+    /// Step back through the game once it's over, trying out other moves along the way.
+    review: bool,
+    /// Only print how the game ended, for scripts to pick up along with the exit code.
+    quiet: bool,
+    /// Which side the human takes against the computer.
+    play_as: Option<Player>,
+    /// Only changes what the cells are called, the game itself always works in reading order.
+    numbering: Numbering,
+    /// Offer to play a move for the human when it's the only one that doesn't lose.
+    auto_forced: bool,
+    /// Stop after this many games, playing them back to back when nobody is at the keyboard.
+    /// <br><br>
+    /// Every game counts on its own, there are no matches of several games to count instead.
+    max_games: Option<usize>,
+    /// Draw the board in the middle of the terminal rather than its top-left corner.
+    center: bool,
+    /// How many spaces go between the cells of a row, one when this isn't set, see [`Options::cell_gap`].
+    spacing: Option<usize>,
+    /// How many blank lines go between the rows of the board.
+    row_gap: usize,
+    /// Announce the result in big letters as well, see [`banner`].
+    banner: bool,
+    /// The slot the game is saved to when the human presses w, see [`SavedGame`].
+    save_slot: Option<String>,
+    /// Pick a saved game to carry on with, or delete some, before playing.
+    slots: bool,
+    /// The saved game the first game carries on from, picked from the `--slots` menu.
+    resume: Option<SavedGame>,
 }
 
-fn check_win(board: &Board) -> Option<Player> {
-    // Vertical
-    {
-        let mut i_col = 0;
+impl Options {
+    fn parse() -> Result<Options, String> {
+        let mut options = Options::default();
 
-        for col in &board[0] {
-            if col == &board[1][i_col] && col == &board[2][i_col] {
-                match col.to_player() {
-                    Ok(player) => return Some(player),
-                    _ => {}
+        let mut args = env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--blind" => options.blind = true,
+                "--reset-scores" => options.reset_scores = true,
+                "--allow-pass" => options.allow_pass = true,
+                "--mirror" => options.mirror = true,
+                "--center" => options.center = true,
+                "--banner" => options.banner = true,
+                "--wild" => options.rules.wild = true,
+                "--no-diagonals" => options.rules.diagonals = false,
+                "--rolling" => options.rules.rolling = true,
+                // The cell is always counted in reading order, whatever numbering the board is drawn with
+                "--hole" => match value(&mut args, &arg)?.parse() {
+                    Ok(index @ 1..=9) => options.rules.holes[index - 1] = true,
+                    _ => return Err(String::from("--hole needs a cell from 1 to 9"))
+                },
+                "--debug-lines" => options.debug_lines = true,
+                "--threats" => options.threats = true,
+                "--teach" => options.teach = true,
+                "--strict" => options.strict = true,
+                "--hotseat-pass" => options.hotseat_pass = true,
+                "--assist" => options.assist = true,
+                "--no-blunders" => options.no_blunders = true,
+                "--auto-forced" => options.auto_forced = true,
+                "--adaptive" => options.adaptive = true,
+                "--step-ai" => options.step_ai = true,
+                "--mirror-ai" => options.noughts = Controller::Mirror,
+                "--external-ai" => {
+                    let command = value(&mut args, &arg)?;
+
+                    if command.trim().is_empty() {
+                        return Err(String::from("--external-ai needs a command to run"));
+                    }
+
+                    options.external_ai = Some(command);
+                    options.noughts = Controller::External;
+                }
+                "--analyze" => options.analyze = true,
+                "--profile-search" => options.profile_search = true,
+                "--summary-after-each" => options.summary = true,
+                "--verbose" => options.verbose = true,
+                "--review" => options.review = true,
+                "--no-footer" => options.hide_footer = true,
+                "--echo-moves" => options.echo_moves = true,
+                "--no-confirm-quit" => options.no_confirm_quit = true,
+                "--annotate" => options.annotate = Some(PathBuf::from(value(&mut args, &arg)?)),
+                "--record" => options.record = Some(PathBuf::from(value(&mut args, &arg)?)),
+                "--replay" => options.replay = Some(PathBuf::from(value(&mut args, &arg)?)),
+                "--export-replay" => options.export_replay = Some(PathBuf::from(value(&mut args, &arg)?)),
+                "--verify" => options.verify = Some(PathBuf::from(value(&mut args, &arg)?)),
+                "--show-evaluation-bar" => options.evaluation_bar = true,
+                "--board-file" => options.board_file = Some(PathBuf::from(value(&mut args, &arg)?)),
+                "--clock" => options.clock = Some(parse_clock(&value(&mut args, &arg)?)?),
+                "--on-illegal" => options.on_illegal = IllegalMovePolicy::parse(&value(&mut args, &arg)?)?,
+                "--draw-policy" => options.draw_policy = DrawPolicy::parse(&value(&mut args, &arg)?)?,
+                "--win-style" => options.win_style = WinStyle::parse(&value(&mut args, &arg)?)?,
+                "--format" => options.format = match value(&mut args, &arg)?.as_str() {
+                    "pretty" => Format::Pretty,
+                    "json" => Format::Json,
+                    format => return Err(format!("Unknown format: {} (expected pretty or json)", format))
+                },
+                "--random-open" => options.random_open = match value(&mut args, &arg)?.parse() {
+                    // Nine moves would fill the board before anyone got to play
+                    Ok(depth @ 0..=8) => Some(depth),
+                    _ => return Err(String::from("--random-open needs a depth from 0 to 8"))
+                },
+                "--seed-board" => options.seed_board = match value(&mut args, &arg)?.parse() {
+                    Ok(seed) => Some(seed),
+                    Err(_) => return Err(String::from("--seed-board needs a whole number"))
+                },
+                "--seed" => options.seed = match value(&mut args, &arg)?.parse() {
+                    Ok(seed) => Some(seed),
+                    Err(_) => return Err(String::from("--seed needs a whole number"))
+                },
+                "--ascii-only" => options.ascii_only = true,
+                "--board-numbering" => options.numbering = Numbering::parse(&value(&mut args, &arg)?)?,
+                "--theme" => {
+                    let theme = Theme::parse(&value(&mut args, &arg)?)?;
+
+                    // Symbols picked by hand are kept whichever order the flags come in
+                    if options.theme.cross == Theme::default().cross {
+                        options.theme.cross = theme.cross;
+                    }
+
+                    if options.theme.nought == Theme::default().nought {
+                        options.theme.nought = theme.nought;
+                    }
                 }
+                "--cross-symbol" => options.theme.cross = value(&mut args, &arg)?,
+                "--nought-symbol" => options.theme.nought = value(&mut args, &arg)?,
+                "--count-moves-limit" => options.rules.move_limit = match value(&mut args, &arg)?.parse() {
+                    Ok(limit) if limit > 0 => Some(limit),
+                    _ => return Err(String::from("--count-moves-limit needs a number of moves above zero"))
+                },
+                "--spacing" => options.spacing = match value(&mut args, &arg)?.parse() {
+                    Ok(spaces @ 0..=MAX_SPACING) => Some(spaces),
+                    _ => return Err(format!("--spacing needs a number of spaces from 0 to {}", MAX_SPACING))
+                },
+                "--row-gap" => options.row_gap = match value(&mut args, &arg)?.parse() {
+                    Ok(lines @ 0..=MAX_SPACING) => lines,
+                    _ => return Err(format!("--row-gap needs a number of blank lines from 0 to {}", MAX_SPACING))
+                },
+                "--delay" => options.delay = match value(&mut args, &arg)?.parse() {
+                    Ok(0) => None,
+                    Ok(millis) => Some(Duration::from_millis(millis)),
+                    Err(_) => return Err(String::from("--delay needs a number of milliseconds"))
+                },
+                "--puzzles" => options.puzzles = Some(PathBuf::from(value(&mut args, &arg)?)),
+                "--show-win" => options.show_win = Some(board_from_line(&value(&mut args, &arg)?)?),
+                "--headless" => options.headless = true,
+                "--quiet" => options.quiet = true,
+                "--selfplay" => options.selfplay = match value(&mut args, &arg)?.parse() {
+                    Ok(games) if games > 0 => Some(games),
+                    _ => return Err(String::from("--selfplay needs a number of games above zero"))
+                },
+                "--ai-vs-ai-tournament" => options.tournament = match value(&mut args, &arg)?.parse() {
+                    Ok(games) if games > 0 => Some(games),
+                    _ => return Err(String::from("--ai-vs-ai-tournament needs a number of games above zero"))
+                },
+                "--max-games" => options.max_games = match value(&mut args, &arg)?.parse() {
+                    Ok(games) if games > 0 => Some(games),
+                    _ => return Err(String::from("--max-games needs a number of games above zero"))
+                },
+                "--fast-draws" => options.fast_draws = true,
+                "--count-games" => options.count_games = true,
+                "--eval-quiz" => options.eval_quiz = true,
+                "--position-stdin" => options.position_stdin = true,
+                "--save-slot" => options.save_slot = Some(check_slot_name(value(&mut args, &arg)?)?),
+                "--slots" => options.slots = true,
+                "--doctor" => options.doctor = true,
+                "--list-variants" => options.list_variants = true,
+                "--dump-tablebase" => options.dump_tablebase = Some(PathBuf::from(value(&mut args, &arg)?)),
+                "--temperature" => options.temperature = match value(&mut args, &arg)?.parse::<f64>() {
+                    Ok(temperature) if temperature.is_finite() && temperature >= 0.0 => Some(temperature),
+                    _ => return Err(String::from("--temperature needs a number from 0 up"))
+                },
+                "--prompt" => options.prompt = Some(check_prompt(value(&mut args, &arg)?)?),
+                "--play-as" => options.play_as = match value(&mut args, &arg)?.to_lowercase().as_str() {
+                    "x" => Some(Player::Crosses),
+                    "o" => Some(Player::Noughts),
+                    side => return Err(format!("Unknown side: {} (expected x or o)", side))
+                },
+                "--crosses" => options.crosses = Controller::parse(&value(&mut args, &arg)?)?,
+                "--noughts" => options.noughts = Controller::parse(&value(&mut args, &arg)?)?,
+                _ => return Err(format!("Unknown argument: {}", arg))
             }
+        }
 
-            i_col += 1;
+        // Marks keep coming off the board, so rolling games can go on forever and their trees can't be walked
+        if options.rules.rolling && (options.count_games || options.dump_tablebase.is_some()) {
+            return Err(String::from("--count-games and --dump-tablebase can't be used with --rolling"));
         }
-    }
 
-    // Horizontal
-    {
-        for row in board {
-            let mut all_equal = true;
+        if options.external_ai.is_some() && (options.selfplay.is_some() || options.tournament.is_some()) {
+            return Err(String::from("--external-ai can't be used with --selfplay or --ai-vs-ai-tournament"));
+        }
 
-            let mut prev = &row[0];
+        if options.selfplay.is_some() && options.tournament.is_some() {
+            return Err(String::from("--selfplay and --ai-vs-ai-tournament can't be used together"));
+        }
 
-            for col in row {
-                if col != prev {
-                    all_equal = false;
-                    break;
-                } else {
-                    prev = &col;
-                };
+        // JSON output already has a line for every game, and anything else would get in the way of reading it
+        if options.summary && options.format == Format::Json {
+            return Err(String::from("--summary-after-each can't be used with --format json"));
+        }
+
+        // Only the tournament keeps points, the other modes count draws as draws
+        if options.draw_policy != DrawPolicy::default() && options.tournament.is_none() {
+            return Err(String::from("--draw-policy can only be used with --ai-vs-ai-tournament"));
+        }
+
+        // The pictures are of recorded games, there's nothing else to draw them from
+        if options.export_replay.is_some() && options.replay.is_none() {
+            return Err(String::from("--export-replay needs --replay FILE"));
+        }
+
+        if options.fast_draws && options.selfplay.is_none() {
+            return Err(String::from("--fast-draws can only be used with --selfplay"));
+        }
+
+        // Marks coming off the board can open lines up again, so no rolling game is ever a dead draw
+        if options.fast_draws && options.rules.rolling {
+            return Err(String::from("--fast-draws can't be used with --rolling"));
+        }
+
+        if options.max_games.is_some() && (options.selfplay.is_some() || options.tournament.is_some()) {
+            return Err(String::from("--max-games can't be used with --selfplay or --ai-vs-ai-tournament"));
+        }
+
+        // The computer's adjusted level would be lost along with the rematches
+        if (options.save_slot.is_some() || options.slots) && options.adaptive {
+            return Err(String::from("--save-slot and --slots can't be used with --adaptive"));
+        }
+
+        if options.slots && (options.random_open.is_some() || options.seed_board.is_some()) {
+            return Err(String::from("--slots can't be used with --random-open or --seed-board"));
+        }
+
+        // A board doesn't say which order its marks were placed in, so nobody could tell which would roll off next
+        if options.show_win.is_some() && options.rules.rolling {
+            return Err(String::from("--show-win can't be used with --rolling"));
+        }
+
+        if options.eval_quiz && options.rules.rolling {
+            return Err(String::from("--eval-quiz can't be used with --rolling"));
+        }
+
+        // Too many holes leave no room for the two to six marks its positions have
+        if options.eval_quiz && options.rules.holes.contains(&true) {
+            return Err(String::from("--eval-quiz can't be used with --hole"));
+        }
+
+        // The overlay says which marks are in each line, which blind games keep hidden
+        if options.teach && options.blind {
+            return Err(String::from("--teach can't be used with --blind"));
+        }
+
+        // Every echoed line has the whole board in it
+        if options.echo_moves && options.blind {
+            return Err(String::from("--echo-moves can't be used with --blind"));
+        }
+
+        let free = options.rules.holes.iter().filter(|hole| !**hole).count();
+
+        // A board of holes is a draw before anyone has moved
+        if free == 0 {
+            return Err(String::from("--hole can't be given for every cell"));
+        }
+
+        // Filling every free cell would leave the game over before anyone got to play
+        if options.random_open.is_some_and(|depth| depth >= free) {
+            return Err(format!("--random-open needs a depth below {} with these holes", free));
+        }
+
+        if options.random_open.is_some() && options.seed_board.is_some() {
+            return Err(String::from("--random-open and --seed-board can't be used together"));
+        }
+
+        // A board doesn't say which marks would roll off next, the same as for --show-win
+        if options.position_stdin && options.rules.rolling {
+            return Err(String::from("--position-stdin can't be used with --rolling"));
+        }
+
+        if options.position_stdin && (options.random_open.is_some() || options.seed_board.is_some() || options.slots) {
+            return Err(String::from("--position-stdin can't be used with --random-open, --seed-board or --slots"));
+        }
+
+        // The computer keeps whatever type it was given, it just moves across to the other side
+        if let Some(side) = options.play_as {
+            let computer = match (options.crosses, options.noughts) {
+                (Controller::Human, Controller::Human) => Controller::Computer(Difficulty::Medium),
+                (Controller::Human, computer) | (computer, Controller::Human) => computer,
+                _ => return Err(String::from("--play-as needs a human player"))
+            };
+
+            match side {
+                Player::Crosses => (options.crosses, options.noughts) = (Controller::Human, computer),
+                Player::Noughts => (options.crosses, options.noughts) = (computer, Controller::Human)
             }
+        }
 
-            if all_equal {
-                match row[0].to_player() {
-                    Ok(player) => return Some(player),
-                    _ => {}
+        // Quiet games never touch the keyboard, so humans can only play them by sending moves in as JSON lines
+        if options.quiet && options.format == Format::Pretty {
+            options.headless = true;
+        }
+
+        // Self-play and tournaments are always headless, nobody is going to sit through thousands of games
+        if options.headless || options.selfplay.is_some() || options.tournament.is_some() {
+            for controller in [&mut options.crosses, &mut options.noughts] {
+                if *controller == Controller::Human {
+                    *controller = Controller::Computer(Difficulty::Easy);
                 }
             }
         }
-    }
 
-    // Diagonal
-    {
-        for row in [
-            // 0usize to make sure that Rust knows all of these are usize
-            [ [0,0], [1,1], [2,2] ],
-            [ [0,2], [1,1], [2,0] ]
-        ] {
-            let mut all_equal = true;
+        // Checked once headless games have handed the humans' sides to the computer
+        if options.hotseat_pass && (options.controllers() != [Controller::Human; 2] || options.format != Format::Pretty) {
+            return Err(String::from("--hotseat-pass needs two human players at the keyboard"));
+        }
 
-            let mut prev = row[0].clone();
+        if let Some(temperature) = options.temperature {
+            if options.adaptive {
+                return Err(String::from("--temperature and --adaptive can't be used together"));
+            }
 
-            for col in row {
-                if board[col[0]][col[1]] != board[prev[0]][prev[1]] {
-                    all_equal = false;
-                    break;
-                } else {
-                    prev = col.clone();
-                };
+            let computers = [&mut options.crosses, &mut options.noughts].into_iter().filter(|controller| matches!(controller, Controller::Computer(_)));
+            let mut any = false;
+
+            for controller in computers {
+                *controller = Controller::Weighted(temperature);
+                any = true;
             }
 
-            if all_equal {
-                match board[row[0][0]][row[0][1]].to_player() {
-                    Ok(player) => return Some(player),
-                    _ => {}
-                }
+            // With nobody else to play it, the computer takes noughts like any other opponent
+            if !any {
+                options.noughts = Controller::Weighted(temperature);
+            }
+        }
+
+        // Plain ASCII wins over any theme, wherever the flags came
+        if options.ascii_only {
+            options.theme = Theme::default();
+        }
+
+        if options.adaptive {
+            match (options.crosses, options.noughts) {
+                (Controller::Human, Controller::Human) => options.noughts = Controller::Computer(Difficulty::Medium),
+                (Controller::Computer(_), Controller::Computer(_)) => return Err(String::from("--adaptive needs a human player")),
+                _ => {}
             }
         }
+
+        // A human who isn't adjusting the computer's level plays one game at a time, the limit is for games that loop
+        if options.max_games.is_some() && !options.adaptive && options.controllers().contains(&Controller::Human) {
+            return Err(String::from("--max-games needs two computer players or --adaptive"));
+        }
+
+        Ok(options)
+    }
+
+    fn controllers(&self) -> [Controller; 2] {
+        [self.crosses, self.noughts]
+    }
+
+    fn controller(&self, player: Player) -> Controller {
+        match player {
+            Player::Crosses => self.crosses,
+            Player::Noughts => self.noughts
+        }
+    }
+
+    /// Whether games are only reported by their summary line, which needs nobody to be playing by looking at the board.
+    fn brief(&self) -> bool {
+        self.summary && !self.verbose && !self.controllers().contains(&Controller::Human)
+    }
+
+    /// The side the human has in a game against the computer.
+    fn human_side(&self) -> Option<Player> {
+        match (self.crosses, self.noughts) {
+            (Controller::Human, Controller::Human) => None,
+            (Controller::Human, _) => Some(Player::Crosses),
+            (_, Controller::Human) => Some(Player::Noughts),
+            _ => None
+        }
     }
 
-    None
+    /// How many spaces to draw between the cells of a row.
+    fn cell_gap(&self) -> usize {
+        self.spacing.unwrap_or(1)
+    }
+
+    /// The computer's difficulty, when it's adjusting to the human.
+    fn adaptive_level(&self) -> Option<Difficulty> {
+        match (self.adaptive, self.crosses, self.noughts) {
+            (true, Controller::Computer(level), _) | (true, _, Controller::Computer(level)) => Some(level),
+            _ => None
+        }
+    }
 }
 
-fn main() {
-    let mut game = Game {
-        board: [
-            [
-                CellState::Empty,
-                CellState::Empty,
-                CellState::Empty
-            ],
-            [
-                CellState::Empty,
-                CellState::Empty,
-                CellState::Empty
-            ],
-            [
-                CellState::Empty,
-                CellState::Empty,
-                CellState::Empty
-            ]
-        ],
-        player: Player::Crosses,
+/// Takes the value following `flag`.
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("{} needs a value", flag))
+}
+
+/// The placeholders a `--prompt` template can use, see [`fill_prompt`].
+const PROMPT_PLACEHOLDERS: [&str; 4] = ["{name}", "{symbol}", "{number}", "{move_no}"];
+
+/// Makes sure every `{...}` in a `--prompt` template is one that gets filled in, so typos show up before the game starts.
+fn check_prompt(template: String) -> Result<String, String> {
+    let mut rest = template.as_str();
+
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end + 1,
+            None => return Err(format!("Unclosed placeholder in --prompt: {}", &rest[start..]))
+        };
+
+        if !PROMPT_PLACEHOLDERS.contains(&&rest[start..end]) {
+            return Err(format!("Unknown placeholder in --prompt: {} (expected {})", &rest[start..end], PROMPT_PLACEHOLDERS.join(", ")));
+        }
+
+        rest = &rest[end..];
+    }
+
+    Ok(template)
+}
+
+/// Fills in a `--prompt` template for the player to move: `{name}` is "Crosses" or "Noughts",
+/// `{symbol}` their mark as the board draws it, `{number}` 1 or 2 and `{move_no}` the number of the move about to be made.
+fn fill_prompt(template: &str, game: &Game, options: &Options) -> String {
+    let symbol = match game.current_player() {
+        Player::Crosses => &options.theme.cross,
+        Player::Noughts => &options.theme.nought
     };
 
-    let term = Term::stdout();
+    template
+        .replace("{name}", game.current_player().to_name())
+        .replace("{symbol}", symbol)
+        .replace("{number}", &game.current_player().to_number().to_string())
+        .replace("{move_no}", &(game.history().len() + 1).to_string())
+}
 
-    loop {
-        match term.clear_screen() {
-            Err(_) => println!("\n==============================\n"),
-            _ => {}
+/// Reads a time control written as `SECONDS` or `SECONDS+INCREMENT`.
+fn parse_clock(text: &str) -> Result<(Duration, Duration), String> {
+    let (limit, increment) = text.split_once('+').unwrap_or((text, "0"));
+
+    let seconds = |text: &str| match text.trim().parse::<f64>() {
+        Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => Ok(Duration::from_secs_f64(seconds)),
+        _ => Err(format!("Invalid time control: {} (expected SECONDS or SECONDS+INCREMENT)", text))
+    };
+
+    let limit = seconds(limit)?;
+
+    if limit.is_zero() {
+        return Err(String::from("The clock needs some time on it!"));
+    }
+
+    Ok((limit, seconds(increment)?))
+}
+
+/// A chess style clock counting down each player's total thinking time.
+struct ChessClock {
+    crosses: Duration,
+    noughts: Duration,
+    increment: Duration,
+    /// When the player to move started thinking.
+    started: Instant,
+}
+
+impl ChessClock {
+    fn new(limit: Duration, increment: Duration) -> ChessClock {
+        ChessClock {
+            crosses: limit,
+            noughts: limit,
+            increment,
+            started: Instant::now(),
+        }
+    }
+
+    fn remaining(&self, player: Player) -> Duration {
+        match player {
+            Player::Crosses => self.crosses,
+            Player::Noughts => self.noughts
         }
+    }
+
+    /// Starts the clock for whoever is about to move.
+    fn start(&mut self) {
+        self.started = Instant::now();
+    }
 
-        draw_board(&game.board);
+    /// The moment `player` runs out of time, if they are the one thinking.
+    fn deadline(&self, player: Player) -> Instant {
+        self.started + self.remaining(player)
+    }
 
-        let mut draw = true;
+    /// Stops the clock after `player` has moved, returning false if their time ran out first.
+    fn stop(&mut self, player: Player) -> bool {
+        let elapsed = self.started.elapsed();
+        let increment = self.increment;
 
-        loop {
+        let remaining = match player {
+            Player::Crosses => &mut self.crosses,
+            Player::Noughts => &mut self.noughts
+        };
 
-            if get_input(&mut game, draw) {
-                break;
-            } else {
-                draw = false;
+        if elapsed >= *remaining {
+            *remaining = Duration::ZERO;
+            return false;
+        }
+
+        *remaining = *remaining - elapsed + increment;
+        true
+    }
+}
+
+/// Formats a clock reading as minutes, seconds and tenths, such as `1:05.3`.
+fn format_clock(time: Duration) -> String {
+    let tenths = time.as_millis() / 100;
+
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+/// Totals of every game played, kept between sessions in a small TOML file.
+#[derive(Default)]
+struct Scoreboard {
+    crosses: u32,
+    noughts: u32,
+    draws: u32,
+    /// Wins in games between a human and the computer, counted for whichever side each of them had.
+    human: u32,
+    computer: u32,
+}
+
+impl Scoreboard {
+    /// The scores file lives at `~/.tictactoe/scores.toml`.
+    fn path() -> Option<PathBuf> {
+        env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(|home| PathBuf::from(home).join(".tictactoe").join("scores.toml"))
+    }
+
+    fn parse(text: &str) -> Result<Scoreboard, &'static str> {
+        let mut scoreboard = Scoreboard::default();
+
+        for line in text.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some(pair) => pair,
+                None => return Err("Expected a `key = value` line!")
+            };
+
+            let value = match value.trim().parse::<u32>() {
+                Ok(value) => value,
+                Err(_) => return Err("Scores must be whole numbers!")
+            };
+
+            match key.trim() {
+                "crosses" => scoreboard.crosses = value,
+                "noughts" => scoreboard.noughts = value,
+                "draws" => scoreboard.draws = value,
+                "human" => scoreboard.human = value,
+                "computer" => scoreboard.computer = value,
+                _ => return Err("Unknown score name!")
             }
         }
 
-        match check_win(&game.board) {
-            Some(player) => {
-                match player {
-                    Player::Noughts => {
-                        println!("Noughts wins!");
-                        break;
-                    },
-                    Player::Crosses => {
-                        println!("Crosses wins!");
-                        break;
-                    }
+        Ok(scoreboard)
+    }
+
+    /// Loads the scores from `path`, starting from zero with a warning if they can't be read.
+    fn load(path: &Path) -> Scoreboard {
+        match fs::read_to_string(path) {
+            Ok(text) => match Scoreboard::parse(&text) {
+                Ok(scoreboard) => scoreboard,
+                Err(error) => {
+                    eprintln!("Warning: {} is corrupt ({}), resetting scores to zero.", path.display(), error);
+                    Scoreboard::default()
+                }
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                eprintln!("Warning: no scores found at {}, starting from zero.", path.display());
+                Scoreboard::default()
+            }
+            Err(error) => {
+                eprintln!("Warning: could not read {} ({}), resetting scores to zero.", path.display(), error);
+                Scoreboard::default()
+            }
+        }
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(
+            path,
+            format!(
+                "crosses = {}\nnoughts = {}\ndraws = {}\nhuman = {}\ncomputer = {}\n",
+                self.crosses, self.noughts, self.draws, self.human, self.computer
+            )
+        )
+    }
+
+    /// Adds a win for `player`, and for the human or the computer when it was one against the other.
+    fn record_win(&mut self, player: Player, options: &Options) {
+        match player {
+            Player::Noughts => self.noughts += 1,
+            Player::Crosses => self.crosses += 1
+        }
+
+        match (options.controller(player), options.human_side()) {
+            (_, None) => {}
+            (Controller::Human, _) => self.human += 1,
+            _ => self.computer += 1
+        }
+    }
+}
+
+/// A game put aside part way through with `--save-slot`, kept as its rules and moves in a small TOML file like the scores:
+/// ```text
+/// diagonals = true
+/// rolling = false
+/// moves = "5 1 pass 9"
+/// ```
+/// Games with `--hole` also have a line like `holes = "1,9"`, games with `--count-moves-limit` one like `move_limit = 6`,
+/// and wild games have `wild = true` and moves like `5o`, see [`moves_to_text`].
+/// The clock isn't kept, a game with a time control starts again with full clocks.
+#[derive(Clone)]
+struct SavedGame {
+    rules: Rules,
+    moves: Vec<Turn>,
+}
+
+impl SavedGame {
+    /// Saves live in `~/.tictactoe/saves`, one `<slot>.toml` file for each slot.
+    fn dir() -> Option<PathBuf> {
+        Scoreboard::path().map(|path| path.with_file_name("saves"))
+    }
+
+    fn path(slot: &str) -> Option<PathBuf> {
+        SavedGame::dir().map(|dir| dir.join(format!("{}.toml", slot)))
+    }
+
+    fn from_game(game: &Game) -> SavedGame {
+        SavedGame { rules: *game.rules(), moves: game_moves(game) }
+    }
+
+    fn parse(text: &str) -> Result<SavedGame, &'static str> {
+        let mut saved = SavedGame { rules: Rules::standard(), moves: Vec::new() };
+
+        for line in text.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err("Expected a `key = value` line!")
+            };
+
+            match (key, value) {
+                ("diagonals" | "rolling" | "wild", "true" | "false") => {
+                    let flag = match key {
+                        "diagonals" => &mut saved.rules.diagonals,
+                        "rolling" => &mut saved.rules.rolling,
+                        _ => &mut saved.rules.wild
+                    };
+
+                    *flag = value == "true";
+                }
+                ("diagonals" | "rolling" | "wild", _) => return Err("Rules must be true or false!"),
+                ("move_limit", _) => match value.parse() {
+                    Ok(limit) if limit > 0 => saved.rules.move_limit = Some(limit),
+                    _ => return Err("The move limit must be a number of moves above zero!")
+                },
+                ("holes", _) => {
+                    let holes = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).ok_or("Holes must be in quotes!")?;
+
+                    saved.rules.holes = parse_holes(holes)?;
+                }
+                ("moves", _) => {
+                    let moves = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).ok_or("Moves must be in quotes!")?;
+
+                    saved.moves = parse_moves(moves)?;
                 }
+                _ => return Err("Unknown setting!")
             }
-            _ => {game.switch()}
         }
+
+        // Replaying the moves now means a broken save is reported when it's listed, not half way into loading it
+        saved.game()?;
+
+        Ok(saved)
+    }
+
+    fn to_text(&self) -> String {
+        let mut text = format!("diagonals = {}\nrolling = {}\n", self.rules.diagonals, self.rules.rolling);
+
+        if self.rules.wild {
+            text.push_str("wild = true\n");
+        }
+
+        if self.rules.holes.contains(&true) {
+            text.push_str(&format!("holes = \"{}\"\n", holes_to_text(&self.rules.holes)));
+        }
+
+        if let Some(limit) = self.rules.move_limit {
+            text.push_str(&format!("move_limit = {}\n", limit));
+        }
+
+        text.push_str(&format!("moves = \"{}\"\n", moves_to_text(&self.moves)));
+        text
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, self.to_text())
+    }
+
+    /// Plays the saved moves out again, which fails if they aren't a game that's still going.
+    fn game(&self) -> Result<Game, &'static str> {
+        let game = replay_moves(self.rules, &self.moves)?;
+
+        match game.outcome() {
+            Some(_) => Err("The saved game is already over!"),
+            None => Ok(game)
+        }
+    }
+}
+
+/// A move of a saved or recorded game, as the 1-based cell and the mark placed there, or `None` for a pass.
+type Turn = Option<(usize, CellState)>;
+
+/// The moves of a game in the order they were played.
+fn game_moves(game: &Game) -> Vec<Turn> {
+    game.history()
+        .iter()
+        .map(|turn| match turn {
+            Move::Place(_, row, cell, mark) => Some((coord_to_index(*row, *cell), *mark)),
+            Move::Pass => None
+        })
+        .collect()
+}
+
+/// Plays a move of a saved or recorded game, which fails if it isn't legal.
+fn play_turn(game: &mut Game, turn: Turn) -> Result<(), &'static str> {
+    match turn {
+        Some((index, mark)) => {
+            let (row, cell) = index_to_coord(index).ok_or("There is no such cell!")?;
+
+            game.make_move(row, cell, mark)
+        }
+        None => {
+            game.pass();
+            Ok(())
+        }
+    }
+}
+
+/// Reads moves written by [`moves_to_text`], working out whose own mark a plain cell number is from the moves before it.
+fn parse_moves(text: &str) -> Result<Vec<Turn>, &'static str> {
+    let mut player = Player::Crosses;
+    let mut moves = Vec::new();
+
+    for word in text.split_whitespace() {
+        let (cell, mark) = match (word.strip_suffix('x'), word.strip_suffix('o')) {
+            (Some(cell), _) => (cell, CellState::Cross),
+            (_, Some(cell)) => (cell, CellState::Nought),
+            _ => (word, player.to_cell())
+        };
+
+        moves.push(match word {
+            "pass" => None,
+            _ => Some((cell.parse().map_err(|_| "Moves must be cell numbers or pass!")?, mark))
+        });
+        player = player.other();
+    }
+
+    Ok(moves)
+}
+
+/// Writes moves as their cell numbers separated by spaces, with `pass` for a pass, such as "5 1 pass 9".
+/// <br><br>
+/// A player who placed the other player's mark, which only wild games allow, has `x` or `o` after the cell, such as "5 5o".
+fn moves_to_text(moves: &[Turn]) -> String {
+    let mut player = Player::Crosses;
+    let mut words = Vec::new();
+
+    for turn in moves {
+        words.push(match turn {
+            None => String::from("pass"),
+            Some((index, mark)) => cell_to_text(*index, *mark, player)
+        });
+        player = player.other();
+    }
+
+    words.join(" ")
+}
+
+/// Writes `player` placing `mark` in the cell with the given 1-based index the way [`moves_to_text`] does, such as "5" or "5o".
+fn cell_to_text(index: usize, mark: CellState, player: Player) -> String {
+    match mark {
+        _ if mark == player.to_cell() => index.to_string(),
+        CellState::Cross => format!("{}x", index),
+        _ => format!("{}o", index)
+    }
+}
+
+/// Writes the cells of the holes in [`Rules::holes`] separated by commas, such as "1,9".
+fn holes_to_text(holes: &[bool; 9]) -> String {
+    let cells: Vec<String> = (1..=9).filter(|index| holes[index - 1]).map(|index| index.to_string()).collect();
+
+    cells.join(",")
+}
+
+/// Reads holes written by [`holes_to_text`].
+fn parse_holes(text: &str) -> Result<[bool; 9], &'static str> {
+    let mut holes = [false; 9];
+
+    for cell in text.split(',') {
+        match cell.trim().parse() {
+            Ok(index @ 1..=9) => holes[index - 1] = true,
+            _ => return Err("Holes must be cells from 1 to 9!")
+        }
+    }
+
+    Ok(holes)
+}
+
+/// Plays `moves` from the empty board, which fails if one of them can't be played.
+fn replay_moves(rules: Rules, moves: &[Turn]) -> Result<Game, &'static str> {
+    let mut game = Game::with_rules(rules);
+
+    for turn in moves {
+        if game.outcome().is_some() {
+            return Err("There are moves after the game is over!");
+        }
+
+        play_turn(&mut game, *turn)?;
+    }
+
+    Ok(game)
+}
+
+/// A finished game as written to the `--record` file, a header line followed by the moves:
+/// ```text
+/// tictactoe date=2026-10-14T09:30 mode=standard size=3 result=X
+/// 5 1 9 pass 3 7 6
+/// ```
+/// The mode is `standard`, or the variants played joined by `+` in the order `rolling`, `no-diagonals` and `wild`,
+/// such as `rolling+wild`, and the result is `X`, `O` or `draw`. Wild games write their moves like saves do, see [`moves_to_text`].
+/// Games with `--hole` also have a field like `holes=1,9`, and games with `--count-moves-limit` one like `limit=6`.
+/// A file can hold any number of records, one after another.
+struct GameRecord {
+    /// When the game finished, in UTC, as written by [`format_date`] with a `T` in place of the space.
+    date: String,
+    rules: Rules,
+    result: GameOutcome,
+    moves: Vec<Turn>,
+}
+
+impl GameRecord {
+    fn from_game(game: &Game, result: GameOutcome) -> GameRecord {
+        GameRecord {
+            date: format_date(SystemTime::now()).replacen(' ', "T", 1),
+            rules: *game.rules(),
+            result,
+            moves: game_moves(game)
+        }
+    }
+
+    fn mode(&self) -> String {
+        let variants = [(self.rules.rolling, "rolling"), (!self.rules.diagonals, "no-diagonals"), (self.rules.wild, "wild")];
+        let played: Vec<&str> = variants.iter().filter(|(on, _)| *on).map(|(_, name)| *name).collect();
+
+        if played.is_empty() { String::from("standard") } else { played.join("+") }
+    }
+
+    /// Reads a record from its header line and the line of moves after it.
+    /// <br><br>
+    /// The moves are played out to check them, and a game that ended on the board has to have ended the way the header says.
+    /// Games can also end early, by resigning or agreeing a dead draw, so an unfinished board is allowed any result.
+    fn parse(header: &str, moves: &str) -> Result<GameRecord, &'static str> {
+        let record = GameRecord::parse_unchecked(header, moves)?;
+
+        match replay_moves(record.rules, &record.moves)?.outcome() {
+            Some(outcome) if outcome != record.result => Err("The moves don't end the way the header says!"),
+            _ => Ok(record)
+        }
+    }
+
+    /// The same as [`GameRecord::parse`], without playing the moves out, for `--verify` to check them one at a time.
+    fn parse_unchecked(header: &str, moves: &str) -> Result<GameRecord, &'static str> {
+        let mut fields = header.split_whitespace();
+
+        if fields.next() != Some("tictactoe") {
+            return Err("A record has to start with a `tictactoe` header!");
+        }
+
+        let (mut date, mut rules, mut size, mut result, mut holes, mut limit) = (None, None, None, None, None, None);
+
+        for field in fields {
+            let (key, value) = field.split_once('=').ok_or("Header fields must be `key=value`!")?;
+
+            let slot = match key {
+                "date" => &mut date,
+                "mode" => &mut rules,
+                "size" => &mut size,
+                "result" => &mut result,
+                "holes" => &mut holes,
+                "limit" => &mut limit,
+                _ => return Err("Unknown header field!")
+            };
+
+            if slot.replace(value).is_some() {
+                return Err("A header field is given twice!");
+            }
+        }
+
+        let date = date.filter(|date| !date.is_empty()).ok_or("The header needs a date!")?;
+
+        let mode = rules.ok_or("The header needs a mode!")?;
+        let mut rules = Rules::standard();
+
+        match mode {
+            "standard" => {}
+            mode => {
+                for variant in mode.split('+') {
+                    let flag = match variant {
+                        "rolling" => &mut rules.rolling,
+                        "no-diagonals" => &mut rules.diagonals,
+                        "wild" => &mut rules.wild,
+                        _ => return Err("Unknown mode!")
+                    };
+
+                    *flag = variant != "no-diagonals";
+                }
+            }
+        }
+
+        let rules = match holes {
+            Some(holes) => Rules { holes: parse_holes(holes)?, ..rules },
+            None => rules
+        };
+
+        let rules = match limit.map(str::parse) {
+            Some(Ok(limit)) if limit > 0 => Rules { move_limit: Some(limit), ..rules },
+            Some(_) => return Err("The limit must be a number of moves above zero!"),
+            None => rules
+        };
+
+        if size.ok_or("The header needs a size!")? != "3" {
+            return Err("Only boards of size 3 can be replayed!");
+        }
+
+        let result = match result.ok_or("The header needs a result!")? {
+            "X" => GameOutcome::Win(Player::Crosses),
+            "O" => GameOutcome::Win(Player::Noughts),
+            "draw" => GameOutcome::Draw,
+            _ => return Err("The result must be X, O or draw!")
+        };
+
+        Ok(GameRecord { date: date.to_string(), rules, result, moves: parse_moves(moves)? })
+    }
+}
+
+fn write_record(w: &mut impl Write, record: &GameRecord) -> io::Result<()> {
+    let result = match record.result {
+        GameOutcome::Win(player) => player.to_letter().to_string(),
+        GameOutcome::Draw => String::from("draw")
+    };
+
+    write!(w, "tictactoe date={} mode={} size=3 result={}", record.date, record.mode(), result)?;
+
+    if record.rules.holes.contains(&true) {
+        write!(w, " holes={}", holes_to_text(&record.rules.holes))?;
+    }
+
+    if let Some(limit) = record.rules.move_limit {
+        write!(w, " limit={}", limit)?;
+    }
+
+    writeln!(w)?;
+    writeln!(w, "{}", moves_to_text(&record.moves))
+}
+
+/// Reads every record in a file written by [`write_record`], skipping blank lines between them.
+/// <br><br>
+/// Errors give the line number of the header of the record that's wrong.
+fn read_records(text: &str) -> Result<Vec<GameRecord>, String> {
+    record_lines(text)
+        .into_iter()
+        .map(|(number, header, moves)| GameRecord::parse(header, moves).map_err(|error| format!("Line {}: {}", number, error)))
+        .collect()
+}
+
+/// Splits a file of records into the 1-based line number of each header, the header and the line of moves after it.
+fn record_lines(text: &str) -> Vec<(usize, &str, &str)> {
+    let mut lines = text.lines().enumerate();
+    let mut records = Vec::new();
+
+    while let Some((number, header)) = lines.next() {
+        if header.trim().is_empty() {
+            continue;
+        }
+
+        // A game that ended before anyone moved has an empty line of moves, so that one isn't skipped
+        let moves = lines.next().map_or("", |(_, moves)| moves);
+
+        records.push((number + 1, header, moves));
+    }
+
+    records
+}
+
+/// Checks every game in a file of records by playing its moves through [`Game::make_move`], printing a line for each game.
+/// <br><br>
+/// Returns whether every game was legal and played out to the end it records, stopping each check at its first illegal move:
+/// ```text
+/// Game 1: legal, X won in 5 moves
+/// Game 2: move 4 (cell 5) is illegal: Cell has already been played!
+/// Game 3: legal but unfinished after 6 moves, though the header says O won
+/// ```
+fn verify_records(text: &str) -> bool {
+    let mut valid = true;
+    let lines = record_lines(text);
+
+    if lines.is_empty() {
+        println!("There are no games to check.");
+        return false;
+    }
+
+    for (game_number, (line, header, moves)) in lines.into_iter().enumerate() {
+        let record = match GameRecord::parse_unchecked(header, moves) {
+            Ok(record) => record,
+            Err(error) => {
+                println!("Game {}: line {} can't be read: {}", game_number + 1, line, error);
+                valid = false;
+                continue;
+            }
+        };
+
+        let describe = |outcome: GameOutcome| match outcome {
+            GameOutcome::Win(player) => format!("{} won", player.to_letter()),
+            GameOutcome::Draw => String::from("drawn")
+        };
+
+        let mut game = Game::with_rules(record.rules);
+        let mut illegal = None;
+
+        for (number, turn) in record.moves.iter().enumerate() {
+            let result = match game.outcome() {
+                Some(_) => Err("The game is already over!"),
+                None => play_turn(&mut game, *turn)
+            };
+
+            if let Err(error) = result {
+                let cell = turn.map_or_else(|| String::from("pass"), |(index, _)| format!("cell {}", index));
+
+                illegal = Some(format!("move {} ({}) is illegal: {}", number + 1, cell, error));
+                break;
+            }
+        }
+
+        let (ok, verdict) = match (illegal, game.outcome()) {
+            (Some(illegal), _) => (false, illegal),
+            (None, Some(outcome)) if outcome == record.result => (true, format!("legal, {} in {} moves", describe(outcome), record.moves.len())),
+            (None, Some(outcome)) => (false, format!("legal, but {} where the header says {}", describe(outcome), describe(record.result))),
+            (None, None) => (false, format!("legal but unfinished after {} moves, though the header says {}", record.moves.len(), describe(record.result)))
+        };
+
+        valid &= ok;
+        println!("Game {}: {}", game_number + 1, verdict);
+    }
+
+    valid
+}
+
+/// Adds the finished game to the `--record` file, if there is one.
+fn append_record(game: &Game, options: &Options, result: GameOutcome) {
+    let path = match &options.record {
+        Some(path) => path,
+        None => return
+    };
+
+    let written = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| write_record(&mut file, &GameRecord::from_game(game, result)));
+
+    if let Err(error) = written {
+        eprintln!("Warning: could not record the game in {} ({}).", path.display(), error);
+    }
+}
+
+/// Reads every game in a `--record` file.
+fn load_records(path: &Path) -> Result<Vec<GameRecord>, String> {
+    fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|text| read_records(&text))
+        .map_err(|error| format!("Could not read the games in {} ({})", path.display(), error))
+}
+
+/// Writes each move of the games in a `--record` file to `dir` as an SVG picture of the board after it,
+/// named `move_01.svg`, `move_02.svg` and so on, which can be put together into an animation.
+/// <br><br>
+/// A file with more than one game gets a directory for each, `game_01` and so on. The directories are made if they're missing.
+fn export_replay(path: &Path, dir: &Path, options: &Options) -> Result<(), String> {
+    let records = load_records(path)?;
+
+    for (number, record) in records.iter().enumerate() {
+        let frames_dir = if records.len() == 1 { dir.to_path_buf() } else { dir.join(format!("game_{:02}", number + 1)) };
+
+        fs::create_dir_all(&frames_dir).map_err(|error| format!("Could not make the directory {} ({})", frames_dir.display(), error))?;
+
+        let frames = replay_frames(record, options);
+
+        for (ply, frame) in frames.iter().enumerate() {
+            let frame_path = frames_dir.join(format!("move_{:02}.svg", ply + 1));
+
+            fs::write(&frame_path, frame).map_err(|error| format!("Could not write {} ({})", frame_path.display(), error))?;
+        }
+
+        println!("Wrote {} pictures to {}.", frames.len(), frames_dir.display());
+    }
+
+    Ok(())
+}
+
+/// An SVG picture of the board after each move of `record`, with the winning line struck through on the last one.
+fn replay_frames(record: &GameRecord, options: &Options) -> Vec<String> {
+    let mut game = Game::with_rules(record.rules);
+    let mut frames = Vec::new();
+
+    for (ply, turn) in record.moves.iter().enumerate() {
+        let player = *game.current_player();
+
+        play_turn(&mut game, *turn).expect("records are checked when they're read");
+
+        let caption = match turn {
+            Some((index, mark)) => format!("Move {}: {} plays {}", ply + 1, player.to_letter(), cell_to_text(options.numbering.label(*index), *mark, player)),
+            None => format!("Move {}: {} passes", ply + 1, player.to_letter())
+        };
+
+        // Only a game that ended on the board has a line to show, not one that was resigned or ran out of time
+        let win = if ply + 1 == record.moves.len() { check_win(game.board(), game.rules()) } else { None };
+
+        frames.push(board_svg(game.board(), &caption, win.as_ref().map(|win| &win.line)));
+    }
+
+    frames
+}
+
+/// How many SVG units wide each cell of [`board_svg`] is.
+const SVG_CELL: usize = 100;
+
+/// Draws `board` as an SVG picture with `caption` under it, striking through `line` if there is one.
+/// <br><br>
+/// Crosses, noughts, holes and the line each have a class of their own, so the picture can be styled afterwards.
+fn board_svg(board: &Board, caption: &str, line: Option<&Line>) -> String {
+    let size = SVG_CELL * 3;
+    let centre = |(row, cell): (usize, usize)| (cell * SVG_CELL + SVG_CELL / 2, row * SVG_CELL + SVG_CELL / 2);
+
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\">\n", size, size + SVG_CELL / 2);
+
+    svg.push_str(&format!("  <rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n", size, size + SVG_CELL / 2));
+
+    for row in 0..3 {
+        for cell in 0..3 {
+            let (x, y) = centre((row, cell));
+            let reach = SVG_CELL * 3 / 10;
+
+            match board[row][cell] {
+                CellState::Cross => svg.push_str(&format!(
+                    "  <path class=\"cross\" d=\"M{} {} L{} {} M{} {} L{} {}\" stroke=\"black\" stroke-width=\"8\"/>\n",
+                    x - reach, y - reach, x + reach, y + reach, x + reach, y - reach, x - reach, y + reach
+                )),
+                CellState::Nought => svg.push_str(&format!("  <circle class=\"nought\" cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"8\"/>\n", x, y, reach)),
+                CellState::Blocked => svg.push_str(&format!("  <rect class=\"hole\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"grey\"/>\n", cell * SVG_CELL, row * SVG_CELL, SVG_CELL, SVG_CELL)),
+                CellState::Empty => {}
+            }
+        }
+    }
+
+    // The grid goes over the holes, so they still read as cells of the board
+    for i in 1..3 {
+        svg.push_str(&format!("  <line x1=\"{0}\" y1=\"0\" x2=\"{0}\" y2=\"{1}\" stroke=\"black\" stroke-width=\"4\"/>\n", i * SVG_CELL, size));
+        svg.push_str(&format!("  <line x1=\"0\" y1=\"{0}\" x2=\"{1}\" y2=\"{0}\" stroke=\"black\" stroke-width=\"4\"/>\n", i * SVG_CELL, size));
+    }
+
+    if let Some(line) = line {
+        let ((x1, y1), (x2, y2)) = (centre(line[0]), centre(line[2]));
+
+        svg.push_str(&format!("  <line class=\"win\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"red\" stroke-width=\"12\" stroke-linecap=\"round\"/>\n", x1, y1, x2, y2));
+    }
+
+    svg.push_str(&format!("  <text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"24\" text-anchor=\"middle\">{}</text>\n", size / 2, size + SVG_CELL / 3, caption));
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/// Shows each game in a `--record` file, its result first and then its moves one key press at a time.
+/// <br><br>
+/// Esc skips the rest of a game.
+fn run_replay(path: &Path, options: &Options) -> Result<(), String> {
+    let records = load_records(path)?;
+    let term = Term::stdout();
+
+    for (number, record) in records.iter().enumerate() {
+        let result = match record.result {
+            GameOutcome::Win(player) => format!("{} won", player.to_name()),
+            GameOutcome::Draw => String::from("Drawn")
+        };
+
+        clear_screen(&term, options);
+        println!("Game {} of {} {} {}, {} rules", number + 1, records.len(), dash(options), record.date.replacen('T', " ", 1), record.mode());
+        println!("{} in {} moves. Press any key to step through it, or Esc to skip it.", result, record.moves.len());
+
+        if read_key(&term, options)? == Key::Escape {
+            continue;
+        }
+
+        step_through(&term, record.rules, &record.moves, options)?;
+    }
+
+    Ok(())
+}
+
+/// Shows `moves` being played from the empty board one key press at a time, with the cells each one changed picked out.
+/// <br><br>
+/// Esc stops before the end. The moves have to be legal, such as those of a record that has been read or a game just played.
+fn step_through(term: &Term, rules: Rules, moves: &[Turn], options: &Options) -> Result<(), String> {
+    let mut game = Game::with_rules(rules);
+
+    for turn in moves {
+        let before = *game.board();
+        let player = *game.current_player();
+
+        play_turn(&mut game, *turn).expect("only legal moves are stepped through");
+        clear_screen(term, options);
+
+        match turn.map(|(index, _)| index) {
+            Some(index) => println!("Move {}: {} plays {}", game.history().len(), player.to_letter(), options.numbering.label(index)),
+            None => println!("Move {}: {} passes", game.history().len(), player.to_letter())
+        }
+
+        draw_board_changes(&before, game.board(), options);
+
+        if read_key(term, options)? == Key::Escape {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Offers to step through the game that just finished, before any rematch is offered, see [`step_through`].
+/// <br><br>
+/// Only a person at the keyboard is asked, games between computers or for other programs go straight on.
+fn offer_replay(term: &Term, game: &Game, options: &Options) -> Result<(), String> {
+    if !someone_watching(options) || game.history().is_empty() {
+        return Ok(());
+    }
+
+    println!("Replay that game? (y/n)");
+
+    if ask_yes_no(term, options)? {
+        step_through(term, *game.rules(), &game_moves(game), options)?;
+    }
+
+    Ok(())
+}
+
+/// Whether a person is at the keyboard to answer questions between games.
+fn someone_watching(options: &Options) -> bool {
+    options.format == Format::Pretty && !options.quiet && !options.headless && options.controllers().contains(&Controller::Human)
+}
+
+/// Offers to carry on with the last game saved, or the `--save-slot` one if that was given, before a new game starts.
+/// Saves that can't be loaded are reported and passed over.
+fn offer_resume(term: &Term, options: &Options) -> Result<Option<(String, SavedGame)>, String> {
+    // The same games the --slots menu can't load into
+    let fresh_start = options.adaptive || options.random_open.is_some() || options.seed_board.is_some() || options.position_stdin;
+
+    if !someone_watching(options) || fresh_start {
+        return Ok(None);
+    }
+
+    let mut latest: Option<(String, SavedGame, Option<SystemTime>)> = None;
+
+    for (name, saved, modified) in list_slots() {
+        if options.save_slot.as_ref().is_some_and(|slot| *slot != name) {
+            continue;
+        }
+
+        match saved {
+            Ok(saved) if latest.as_ref().is_none_or(|(_, _, saved_at)| modified > *saved_at) => latest = Some((name, saved, modified)),
+            Ok(_) => {}
+            Err(error) => println!("Skipping save slot {}, it can't be loaded ({}).", name, error)
+        }
+    }
+
+    let (name, saved, _) = match latest {
+        Some(latest) => latest,
+        None => return Ok(None)
+    };
+
+    println!("Resume saved game? (y/n) {} slot {}, {} moves in", dash(options), name, saved.moves.len());
+
+    Ok(ask_yes_no(term, options)?.then_some((name, saved)))
+}
+
+/// Makes sure a slot name can be used as a file name without reaching outside the saves directory.
+fn check_slot_name(name: String) -> Result<String, String> {
+    if name.is_empty() || !name.chars().all(|char| char.is_ascii_alphanumeric() || char == '-' || char == '_') {
+        return Err(format!("Slot names can only use letters, digits, - and _, not {:?}", name));
+    }
+
+    Ok(name)
+}
+
+/// Saves the game to its `--save-slot`, saying whether that worked.
+fn save_to_slot(game: &Game, options: &Options) -> bool {
+    let slot = options.save_slot.as_deref().unwrap_or_default();
+
+    let path = match SavedGame::path(slot) {
+        Some(path) => path,
+        None => {
+            println!("No home directory found, so there's nowhere to save the game.");
+            return false;
+        }
+    };
+
+    match SavedGame::from_game(game).save(&path) {
+        Ok(()) => {
+            println!("Saved to slot {} {} load it again from the --slots menu.", slot, dash(options));
+            true
+        }
+        Err(error) => {
+            println!("Could not save the game to {} ({}).", path.display(), error);
+            false
+        }
+    }
+}
+
+/// Every saved slot by name, with the save or why it can't be loaded, and when it was last saved.
+fn list_slots() -> Vec<(String, Result<SavedGame, String>, Option<SystemTime>)> {
+    let entries = match SavedGame::dir().map(fs::read_dir) {
+        Some(Ok(entries)) => entries,
+        _ => return Vec::new()
+    };
+
+    let mut slots: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_stem()?.to_str()?.to_string();
+
+            if path.extension()? != "toml" {
+                return None;
+            }
+
+            let saved = match fs::read_to_string(&path) {
+                Ok(text) => SavedGame::parse(&text).map_err(String::from),
+                Err(error) => Err(error.to_string())
+            };
+
+            Some((name, saved, entry.metadata().and_then(|metadata| metadata.modified()).ok()))
+        })
+        .collect();
+
+    slots.sort_by(|a, b| a.0.cmp(&b.0));
+    slots
+}
+
+/// Deletes a save slot, saying how that went, which isn't treated as an error if it's already gone.
+fn delete_slot(slot: &str) -> String {
+    let result = match SavedGame::path(slot) {
+        Some(path) => fs::remove_file(path),
+        None => Err(io::Error::from(io::ErrorKind::NotFound))
+    };
+
+    match result {
+        Ok(()) => format!("Deleted slot {}.", slot),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => format!("There's no save slot named {}.", slot),
+        Err(error) => format!("Could not delete slot {} ({}).", slot, error)
+    }
+}
+
+/// A slot as the `--slots` menu lists it, such as "1. morning — 4 moves, saved 2026-10-14 09:30".
+fn slot_line(number: usize, (name, saved, modified): &(String, Result<SavedGame, String>, Option<SystemTime>), options: &Options) -> String {
+    let when = modified.map_or_else(String::new, |time| format!(", saved {}", format_date(time)));
+
+    match saved {
+        Ok(saved) => format!("{}. {} {} {} moves{}", number, name, dash(options), saved.moves.len(), when),
+        Err(error) => format!("{}. {} {} can't be loaded ({}){}", number, name, dash(options), error, when)
+    }
+}
+
+/// What to say when there's nothing in the `--slots` menu.
+const NO_SLOTS: &str = "There are no saved games, save one by pressing w during a game started with --save-slot NAME.";
+
+/// Lists the save slots so one can be loaded or deleted, returning the one to play on with its name.
+/// <br><br>
+/// At a terminal the slots are picked with the arrow keys, see [`pick_slot`]. Input piped in is read a line at a time instead,
+/// since there are no keys to read: a slot's number loads it and d with a number, such as d2, deletes it.
+fn run_slot_menu(options: &Options) -> Result<Option<(String, SavedGame)>, String> {
+    let term = Term::stdout();
+
+    if term.is_term() {
+        return pick_slot(&term, options);
+    }
+
+    loop {
+        let slots = list_slots();
+
+        if slots.is_empty() {
+            println!("{}", NO_SLOTS);
+            return Ok(None);
+        }
+
+        println!("Save slots:");
+
+        for (number, slot) in slots.iter().enumerate() {
+            println!("  {}", slot_line(number + 1, slot, options));
+        }
+
+        print!("Type a number to load that slot, d and a number to delete one (such as d2), or q to quit: ");
+        let _ = io::stdout().flush();
+
+        // Standard input rather than the terminal, which `console` won't read lines from when it's piped in
+        let mut line = String::new();
+
+        if matches!(io::stdin().read_line(&mut line), Ok(0) | Err(_)) {
+            return Err(input_closed(options));
+        }
+
+        let line = line.trim().to_lowercase();
+
+        let (delete, number) = match line.strip_prefix('d') {
+            Some(number) => (true, number.trim()),
+            None => (false, line.as_str())
+        };
+
+        if line.is_empty() || line == "q" {
+            return Ok(None);
+        }
+
+        let slot = match number.parse::<usize>() {
+            Ok(number) if (1..=slots.len()).contains(&number) => slots.into_iter().nth(number - 1).expect("the number was checked to be in range"),
+            _ => {
+                println!("There's no slot {}.", number);
+                continue;
+            }
+        };
+
+        match slot {
+            (name, _, _) if delete => println!("{}", delete_slot(&name)),
+            (name, Ok(saved), _) => return Ok(Some((name, saved))),
+            (name, Err(_), _) => println!("Slot {} can't be loaded, but it can be deleted.", name)
+        }
+    }
+}
+
+/// The `--slots` menu at a terminal, with the slot Enter would load highlighted.
+/// <br><br>
+/// Up and Down move the highlight, stopping at the first and last slots rather than wrapping round, the same as
+/// the `--teach` cursor stops at the edge of the board. A slot's number moves the highlight straight to it, d deletes
+/// the highlighted slot, and Esc or q quits without playing.
+fn pick_slot(term: &Term, options: &Options) -> Result<Option<(String, SavedGame)>, String> {
+    let mut selected = 0;
+
+    // What the last key did, said under the list once it's drawn again
+    let mut notice: Option<String> = None;
+
+    loop {
+        let slots = list_slots();
+
+        if slots.is_empty() {
+            if let Some(notice) = notice {
+                println!("{}", notice);
+            }
+
+            println!("{}", NO_SLOTS);
+            return Ok(None);
+        }
+
+        selected = selected.min(slots.len() - 1);
+
+        clear_screen(term, options);
+        println!("Save slots:");
+
+        for (number, slot) in slots.iter().enumerate() {
+            let line = slot_line(number + 1, slot, options);
+
+            if number == selected {
+                println!("{}", style(format!("> {}", line)).reverse());
+            } else {
+                println!("  {}", line);
+            }
+        }
+
+        println!();
+
+        if let Some(notice) = notice.take() {
+            println!("{}", notice);
+        }
+
+        println!("Up/Down to pick a slot and Enter to load it, d to delete it, or Esc to quit.");
+
+        match read_key(term, options)? {
+            Key::ArrowUp => selected = selected.saturating_sub(1),
+            Key::ArrowDown => selected = (selected + 1).min(slots.len() - 1),
+            Key::Char(char) if char.is_ascii_digit() => match char.to_digit(10).map(|digit| digit as usize) {
+                Some(number) if (1..=slots.len()).contains(&number) => selected = number - 1,
+                _ => notice = Some(format!("There's no slot {}.", char))
+            },
+            Key::Enter => match slots.into_iter().nth(selected).expect("the highlight is always on a slot") {
+                (name, Ok(saved), _) => return Ok(Some((name, saved))),
+                (name, Err(_), _) => notice = Some(format!("Slot {} can't be loaded, but it can be deleted.", name))
+            },
+            Key::Char('d') => notice = Some(delete_slot(&slots[selected].0)),
+            Key::Escape | Key::Char('q') => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// Formats a time as a UTC date and time, such as "2026-10-14 09:30".
+fn format_date(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+
+    // Days since 1970 to a calendar date, from Howard Hinnant's `civil_from_days`
+    let days = seconds / 86_400 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{}-{:02}-{:02} {:02}:{:02}", year, month, day, seconds / 3600 % 24, seconds / 60 % 60)
+}
+
+/// The letters [`banner`] can draw, five rows of `#` each.
+fn banner_glyph(letter: char) -> Option<[&'static str; 5]> {
+    match letter {
+        'A' => Some([" ### ", "#   #", "#####", "#   #", "#   #"]),
+        'D' => Some(["#### ", "#   #", "#   #", "#   #", "#### "]),
+        'I' => Some(["###", " # ", " # ", " # ", "###"]),
+        'N' => Some(["#   #", "##  #", "# # #", "#  ##", "#   #"]),
+        'O' => Some([" ### ", "#   #", "#   #", "#   #", " ### "]),
+        'R' => Some(["#### ", "#   #", "#### ", "#  # ", "#   #"]),
+        'S' => Some([" ####", "#    ", " ### ", "    #", "#### "]),
+        'W' => Some(["#   #", "#   #", "# # #", "## ##", "#   #"]),
+        'X' => Some(["#   #", " # # ", "  #  ", " # # ", "#   #"]),
+        ' ' => Some(["  ", "  ", "  ", "  ", "  "]),
+        _ => None
+    }
+}
+
+/// `text` in letters five rows high, such as "X WINS", or `None` if it has a letter the font doesn't have:
+/// ```text
+/// #   #    #   # ### #   #  ####
+///  # #     #   #  #  ##  # #
+///   #      # # #  #  # # #  ###
+///  # #     ## ##  #  #  ##     #
+/// #   #    #   # ### #   # ####
+/// ```
+fn banner(text: &str) -> Option<Vec<String>> {
+    let glyphs: Vec<[&str; 5]> = text.chars().map(banner_glyph).collect::<Option<_>>()?;
+
+    Some((0..5).map(|row| glyphs.iter().map(|glyph| glyph[row]).collect::<Vec<_>>().join(" ")).collect())
+}
+
+/// Prints the `--banner` for a result, unless the terminal is too narrow for it.
+/// <br><br>
+/// The plain message that follows is printed either way, so nothing is lost when the banner is left out.
+fn print_banner(text: &str, options: &Options) {
+    if !options.banner || options.quiet {
+        return;
+    }
+
+    let rows = match banner(text) {
+        Some(rows) => rows,
+        None => return
+    };
+
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+    if Term::stdout().size_checked().is_some_and(|(_, columns)| usize::from(columns) < width) {
+        return;
+    }
+
+    for row in rows {
+        let row = row.trim_end();
+
+        if options.ascii_only {
+            println!("{}", row);
+        } else {
+            println!("{}", style(row.replace('#', "█")).green().bold());
+        }
+    }
+
+    println!();
+}
+
+/// Names the winning line and its cells as the players see them numbered, such as "the left column (cells 1, 4, 7)".
+fn describe_line(win: &WinInfo, options: &Options) -> String {
+    let cells: Vec<String> = win.line.iter().map(|(row, col)| options.numbering.label(coord_to_index(*row, *col)).to_string()).collect();
+
+    format!("{} (cells {})", line_name(&win.line, 3), cells.join(", "))
+}
+
+fn get_cell(state: &CellState, cell: usize, theme: &Theme) -> String {
+    let text = match state {
+        CellState::Empty => { cell.to_string() }
+        CellState::Nought => { theme.nought.clone() }
+        CellState::Cross => { theme.cross.clone() }
+        CellState::Blocked => { String::from("#") }
+    };
+
+    // Pad by how wide the text looks rather than its length, emoji take up two columns in one character
+    let padding = theme.cell_width().saturating_sub(UnicodeWidthStr::width(text.as_str()));
+
+    format!("[{}{}]", text, " ".repeat(padding))
+}
+
+fn draw_board(board: &Board, options: &Options) {
+    draw_board_highlighted(board, options, &[]);
+}
+
+/// How many blank lines and spaces put the board in the middle of the terminal, or none when it isn't being centred.
+/// <br><br>
+/// The size is asked for on every draw so the board follows the window when it's resized.
+/// Terminals that can't say how big they are get the board in the top-left corner.
+fn board_margin(options: &Options) -> (usize, usize) {
+    let size = match Term::stdout().size_checked() {
+        Some(size) if options.center => size,
+        _ => return (0, 0)
+    };
+
+    let (rows, columns) = (usize::from(size.0), usize::from(size.1));
+
+    (rows.saturating_sub(board_height(options)) / 2, columns.saturating_sub(board_width(options)) / 2)
+}
+
+/// The most `--spacing` and `--row-gap` allow, past which the board stops looking like one.
+const MAX_SPACING: usize = 4;
+
+/// How many columns the board takes up, three bracketed cells to a row with [`Options::cell_gap`] spaces between each.
+fn board_width(options: &Options) -> usize {
+    3 * (options.theme.cell_width() + 2) + 2 * options.cell_gap()
+}
+
+/// How many lines the board takes up, three rows with `--row-gap` blank lines between each.
+fn board_height(options: &Options) -> usize {
+    3 + 2 * options.row_gap
+}
+
+/// The fewest rows a turn fits in with the rows of the board drawn together: the status line, the board and the footer and prompt below it.
+const MIN_ROWS: usize = 8;
+
+/// How often the size of the terminal is checked while waiting, so a resized window gets redrawn.
+const RESIZE_CHECK: Duration = Duration::from_millis(250);
+
+/// Waits for the window to be made bigger while the terminal is too small to draw a turn in.
+/// <br><br>
+/// Terminals that can't say how big they are are assumed to have room.
+fn wait_for_room(term: &Term, options: &Options) {
+    let fits = || match term.size_checked() {
+        Some((rows, columns)) => usize::from(rows) >= MIN_ROWS + board_height(options) - 3 && usize::from(columns) >= board_width(options),
+        None => true
+    };
+
+    if fits() {
+        return;
+    }
+
+    redraw_screen(term, options);
+    println!("Terminal too small {} make the window bigger to carry on.", dash(options));
+
+    while !fits() {
+        thread::sleep(RESIZE_CHECK);
+    }
+}
+
+/// Draws the board with the cells at the `highlight` coordinates picked out in colour.
+fn draw_board_highlighted(board: &Board, options: &Options, highlight: &[(usize, usize)]) {
+    draw_board_glowing(board, options, highlight, Glow::Bright);
+}
+
+/// The same as [`draw_board_highlighted`], picking the cells out only as brightly as `glow`.
+fn draw_board_glowing(board: &Board, options: &Options, highlight: &[(usize, usize)], glow: Glow) {
+    let (top, left) = board_margin(options);
+
+    print!("{}", "\n".repeat(top));
+
+    for (i_row, row) in board.iter().enumerate() {
+        if i_row > 0 {
+            print!("{}", "\n".repeat(options.row_gap));
+        }
+
+        let mut cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i_col, state)| {
+                let cell = get_cell(state, options.numbering.label(coord_to_index(i_row, i_col)), &options.theme);
+
+                if highlight.contains(&(i_row, i_col)) && !options.ascii_only {
+                    match glow {
+                        Glow::Dim => style(cell).green().dim().to_string(),
+                        Glow::Plain => style(cell).green().to_string(),
+                        Glow::Bright => style(cell).green().bold().to_string()
+                    }
+                } else {
+                    cell
+                }
+            })
+            .collect();
+
+        // Only the drawing order changes, the numbers still refer to the real cells
+        if options.mirror {
+            cells.reverse();
+        }
+
+        println!("{}{}", " ".repeat(left), cells.join(&" ".repeat(options.cell_gap())));
+    }
+
+    if options.debug_lines {
+        for (line, player) in completed_lines(board, &options.rules) {
+            let cells: Vec<String> = line.iter().map(|(row, col)| options.numbering.label(coord_to_index(*row, *col)).to_string()).collect();
+
+            println!("Debug: line {} is owned by {}", cells.join("-"), player.to_letter());
+        }
+    }
+}
+
+/// Draws `after` with the cells that changed since `before` picked out, including any that were emptied.
+fn draw_board_changes(before: &Board, after: &Board, options: &Options) {
+    let changed: Vec<(usize, usize)> = board_diff(before, after).iter().filter_map(|(index, _)| index_to_coord(*index)).collect();
+
+    draw_board_highlighted(after, options, &changed);
+}
+
+/// Clears the terminal, or prints a separator if it can't be cleared or escape codes aren't allowed.
+fn clear_screen(term: &Term, options: &Options) {
+    if options.ascii_only || term.clear_screen().is_err() {
+        println!("\n==============================\n");
+    }
+}
+
+/// The dash used to break up messages, which has to be a plain hyphen in ASCII only mode.
+fn dash(options: &Options) -> &'static str {
+    if options.ascii_only { "-" } else { "—" }
+}
+
+/// Which keys play a move, such as "press 1–9, arrows, or h for help", with the arrows only when `--teach` has a cursor to move.
+fn key_hint(options: &Options) -> String {
+    let range = if options.ascii_only { "1-9" } else { "1–9" };
+    let arrows = if options.teach { " arrows," } else { "" };
+
+    format!("press {},{} or h for help", range, arrows)
+}
+
+/// Whether the terminal understands cursor movement, so turns can be redrawn in place.
+fn supports_cursor(term: &Term) -> bool {
+    term.is_term() && env::var("TERM").map_or(true, |name| name != "dumb")
+}
+
+/// Moves back to the top of the screen and wipes everything below it, leaving the scroll-back alone.
+/// <br><br>
+/// This avoids the flicker of clearing the whole screen, terminals without cursor movement get [`clear_screen`] instead.
+fn redraw_screen(term: &Term, options: &Options) {
+    if options.ascii_only || !supports_cursor(term) || term.move_cursor_to(0, 0).and_then(|_| term.clear_to_end_of_screen()).is_err() {
+        clear_screen(term, options);
+    }
+}
+
+/// The line at the top of every turn, saying how far the game has got, what was last played and how many cells are left.
+/// <br><br>
+/// In blind games the count is all there is to go on about the board.
+fn status_line(game: &Game, options: &Options) -> String {
+    let last = match game.history().last() {
+        None => String::from("new game"),
+        // Naming the cell would give away part of the board
+        Some(Move::Place(player, _, _, _)) if options.blind => format!("{} has moved", player.to_letter()),
+        Some(Move::Place(player, row, cell, _)) => format!("{} played {}", player.to_letter(), options.numbering.label(coord_to_index(*row, *cell))),
+        Some(Move::Pass) => format!("{} passed", game.current_player().other().to_letter())
+    };
+
+    let left = game.board().count(&CellState::Empty);
+
+    match options.adaptive_level() {
+        Some(level) => format!("Move {} - {} - Cells left: {} - Level: {}", game.history().len() + 1, last, left, level.to_name()),
+        None => format!("Move {} - {} - Cells left: {}", game.history().len() + 1, last, left)
+    }
+}
+
+/// Redraws the screen with everything shown before a move.
+fn draw_turn(term: &Term, game: &Game, options: &Options, clock: Option<&ChessClock>) {
+    let marked: Vec<usize> = opponent_move(game, options).into_iter().collect();
+
+    draw_turn_at(term, game, options, clock, &marked);
+}
+
+/// The cell the computer just played in, when it's a human's turn against it, so the move isn't missed.
+/// <br><br>
+/// Blind games keep it to themselves along with the rest of the board.
+fn opponent_move(game: &Game, options: &Options) -> Option<usize> {
+    let mover = *game.current_player();
+
+    match game.history().last() {
+        Some(Move::Place(player, row, cell, _)) if !options.blind && options.controller(mover) == Controller::Human && options.controller(*player) != Controller::Human => {
+            Some(coord_to_index(*row, *cell))
+        }
+        _ => None
+    }
+}
+
+/// The same as [`draw_turn`], picking out the 1-based cells in `marked` instead, such as the `--teach` cursor.
+fn draw_turn_at(term: &Term, game: &Game, options: &Options, clock: Option<&ChessClock>, marked: &[usize]) {
+    wait_for_room(term, options);
+    redraw_screen(term, options);
+
+    if options.ascii_only {
+        println!("{}", status_line(game, options));
+    } else {
+        println!("{}", style(status_line(game, options)).bold().cyan());
+    }
+
+    if let Some(clock) = clock {
+        println!(
+            "Clock - X: {}, O: {}",
+            format_clock(clock.remaining(Player::Crosses)),
+            format_clock(clock.remaining(Player::Noughts))
+        );
+    }
+
+    if options.threats {
+        println!(
+            "Threats - X: {}, O: {}",
+            winning_moves(game.board(), Player::Crosses, game.rules()).len(),
+            winning_moves(game.board(), Player::Noughts, game.rules()).len()
+        );
+    }
+
+    // Blind games only show the empty board so players can learn the numbering
+    if !options.blind || game.history().is_empty() {
+        let highlight: Vec<(usize, usize)> = marked.iter().filter_map(|index| index_to_coord(*index)).collect();
+
+        draw_board_highlighted(game.board(), options, &highlight);
+
+        if let Some(index) = opponent_move(game, options) {
+            println!("Opponent played {}", options.numbering.label(index));
+        }
+
+        if let Some((row, cell)) = game.next_to_roll(*game.current_player()) {
+            println!("Your oldest mark, in cell {}, is removed when you play.", options.numbering.label(coord_to_index(row, cell)));
+        }
+    }
+
+    if options.evaluation_bar {
+        println!("{}", evaluation_bar(game));
+    }
+}
+
+/// A gauge of how the game ends with perfect play from here, filled towards the side that's winning.
+/// <br><br>
+/// Tic tac toe positions are only ever won, drawn or lost, so the bar only has three places to be:
+/// ```text
+/// X [###......] O  X is winning
+/// X [...###...] O  Drawn
+/// X [......###] O  O is winning
+/// ```
+fn evaluation_bar(game: &Game) -> String {
+    let (bar, label) = match evaluate(game) {
+        GameOutcome::Win(Player::Crosses) => ("###......", "X is winning"),
+        GameOutcome::Draw => ("...###...", "Drawn"),
+        GameOutcome::Win(Player::Noughts) => ("......###", "O is winning")
+    };
+
+    format!("X [{}] O  {}", bar, label)
+}
+
+/// Animates the winning line in the `--win-style`, waiting `delay` between each frame.
+/// <br><br>
+/// Pressing any key skips the rest of the animation, leaving the caller to draw the line highlighted as it ends up.
+fn animate_win(term: &Term, board: &Board, options: &Options, line: &Line, delay: Duration) {
+    let frames = match options.win_style {
+        WinStyle::Blink => blink_frames(line),
+        WinStyle::Sweep => sweep_frames(line, options),
+        WinStyle::Pulse => pulse_frames(line),
+        WinStyle::None => Vec::new()
+    };
+
+    for (lit, glow) in frames {
+        redraw_screen(term, options);
+        draw_board_glowing(board, options, &lit, glow);
+
+        // Errors mean keys can't be waited on, so there's no way to pace the animation either
+        if !matches!(read_key_timeout(term, delay), Ok(None)) {
+            return;
+        }
+    }
+}
+
+/// How many times the blink and pulse animations repeat.
+const WIN_REPEATS: usize = 3;
+
+/// The winning cells switched on and off.
+fn blink_frames(line: &Line) -> Vec<(Vec<(usize, usize)>, Glow)> {
+    (0..WIN_REPEATS).flat_map(|_| [(line.to_vec(), Glow::Bright), (Vec::new(), Glow::Bright)]).collect()
+}
+
+/// The winning cells lit one more at a time, in the order they're drawn from left to right.
+fn sweep_frames(line: &Line, options: &Options) -> Vec<(Vec<(usize, usize)>, Glow)> {
+    let mut cells = line.to_vec();
+
+    // Mirrored boards draw the last column on the left
+    cells.sort_by_key(|(row, col)| (if options.mirror { 2 - col } else { *col }, *row));
+
+    (1..=cells.len()).map(|shown| (cells[..shown].to_vec(), Glow::Bright)).collect()
+}
+
+/// The winning cells faded up and back down.
+fn pulse_frames(line: &Line) -> Vec<(Vec<(usize, usize)>, Glow)> {
+    (0..WIN_REPEATS).flat_map(|_| [Glow::Dim, Glow::Plain, Glow::Bright, Glow::Plain]).map(|glow| (line.to_vec(), glow)).collect()
+}
+
+/// Waits for a key press, failing once no more keys can ever arrive.
+/// <br><br>
+/// `console` hands back [`Key::Unknown`] straight away when the output isn't a terminal, and fails when there's
+/// no terminal to read from, so a loop waiting for the right key would otherwise spin forever.
+fn read_key(term: &Term, options: &Options) -> Result<Key, String> {
+    match term.read_key() {
+        Ok(Key::Unknown) if !term.is_term() => Err(input_closed(options)),
+        Ok(key) => Ok(key),
+        Err(_) => Err(input_closed(options))
+    }
+}
+
+/// Why the session ends when nothing more can be typed, which is handed back up to [`main`] so the scores are still saved.
+fn input_closed(options: &Options) -> String {
+    format!("Input closed {} exiting.", dash(options))
+}
+
+/// Where the players in a game on the console say why the session has to end, such as their input closing,
+/// shared with [`play`] the way the clock is.
+/// <br><br>
+/// A [`Strategy`] can only hand back an [`Action`], so a player that can't go on records why and quits,
+/// and [`play`] passes the reason up to [`main`], which saves the scores before exiting with an error.
+#[derive(Clone, Default)]
+struct Stopped(Rc<RefCell<Option<String>>>);
+
+impl Stopped {
+    /// Records why the session has to end, keeping the first reason if there's already one, and quits the game.
+    fn stop(&self, reason: String) -> Action {
+        self.0.borrow_mut().get_or_insert(reason);
+        Action::Quit
+    }
+
+    /// Why the session has to end, if a player has said.
+    fn reason(&self) -> Option<String> {
+        self.0.borrow().clone()
+    }
+
+    /// Panics with the reason if the session has to end, for [`Strategy::choose`], which can't hand it back.
+    /// The game loop only ever asks for whole turns, so this is never reached from [`play`].
+    fn check(&self) {
+        if let Some(reason) = self.reason() {
+            panic!("{}", reason);
+        }
+    }
+}
+
+/// Blanks the screen between two humans' turns and waits for the next player to be ready.
+/// <br><br>
+/// Anything typed before the screen went up is thrown away, so a key the last player pressed once too often
+/// can't become the next player's move.
+fn pass_screen(term: &Term, player: Player, options: &Options) -> Result<(), String> {
+    clear_screen(term, options);
+    println!("Pass to Player {} {} press any key when ready.", player.to_letter(), dash(options));
+
+    discard_input();
+    read_key(term, options)?;
+
+    Ok(())
+}
+
+/// Throws away any keys that have been typed but not read yet.
+#[cfg(unix)]
+fn discard_input() {
+    use std::os::unix::io::AsRawFd;
+
+    // The same terminal `console` reads from, see `read_key_timeout`
+    if unsafe { libc::isatty(libc::STDIN_FILENO) } == 1 {
+        unsafe { libc::tcflush(libc::STDIN_FILENO, libc::TCIFLUSH) };
+    } else if let Ok(tty) = fs::File::open("/dev/tty") {
+        unsafe { libc::tcflush(tty.as_raw_fd(), libc::TCIFLUSH) };
+    }
+}
+
+/// Other terminals keep their typed-ahead keys.
+#[cfg(not(unix))]
+fn discard_input() {}
+
+/// Waits up to `timeout` for a key press, returning `None` if nothing was pressed in time.
+#[cfg(unix)]
+fn read_key_timeout(term: &Term, timeout: Duration) -> io::Result<Option<Key>> {
+    use std::os::unix::io::AsRawFd;
+
+    // Read from the same terminal `console` does, which is /dev/tty when stdin is redirected
+    let tty;
+    let fd = if unsafe { libc::isatty(libc::STDIN_FILENO) } == 1 {
+        libc::STDIN_FILENO
+    } else {
+        tty = fs::File::open("/dev/tty")?;
+        tty.as_raw_fd()
+    };
+
+    let mut original = std::mem::MaybeUninit::uninit();
+
+    if unsafe { libc::tcgetattr(fd, original.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let original = unsafe { original.assume_init() };
+    let mut raw = original;
+
+    // The terminal has to be raw while waiting, otherwise keys only arrive once Enter is pressed
+    unsafe { libc::cfmakeraw(&mut raw) };
+
+    if unsafe { libc::tcsetattr(fd, libc::TCSADRAIN, &raw) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+
+    let ready = unsafe { libc::poll(&mut pollfd, 1, timeout.as_millis().min(i32::MAX as u128) as i32) };
+    let poll_error = io::Error::last_os_error();
+
+    // Restore the terminal before reading, so that Ctrl+C can't leave it raw
+    unsafe { libc::tcsetattr(fd, libc::TCSADRAIN, &original) };
+
+    match ready {
+        0 => Ok(None),
+        ready if ready < 0 => Err(poll_error),
+        _ => term.read_key().map(Some)
+    }
+}
+
+/// Terminals without `poll` can't time out, so this just waits for the key.
+#[cfg(not(unix))]
+fn read_key_timeout(term: &Term, _timeout: Duration) -> io::Result<Option<Key>> {
+    term.read_key().map(Some)
+}
+
+/// Prints the state of the game as a single line of JSON, see [`game_json`].
+fn print_json(game: &Game, outcome: Option<&str>) {
+    println!("{}", game_json(game, outcome));
+}
+
+/// The state of the game as a single line of JSON.
+/// <br><br>
+/// `outcome` is `"X"`, `"O"` or `"draw"` once the game is over.
+fn game_json(game: &Game, outcome: Option<&str>) -> String {
+    let legal_moves: Vec<String> = match outcome {
+        Some(_) => Vec::new(),
+        None => game.legal_moves().map(|index| index.to_string()).collect()
+    };
+
+    let move_times: Vec<String> = game
+        .move_times()
+        .iter()
+        .map(|(index, took)| format!("{{\"cell\":{},\"ms\":{}}}", index, took.as_millis()))
+        .collect();
+
+    format!(
+        "{{\"board\":\"{}\",\"player\":\"{}\",\"legal_moves\":[{}],\"move_times\":[{}],\"outcome\":{}}}",
+        board_to_line(game.board()),
+        game.current_player().to_letter(),
+        legal_moves.join(","),
+        move_times.join(","),
+        match outcome {
+            Some(outcome) => format!("\"{}\"", outcome),
+            None => String::from("null")
+        }
+    )
+}
+
+/// Reads a move written as a bare cell number or as `{"move": 5}`, with `pass` in place of the number to pass.
+fn parse_move_line(line: &str) -> Result<Action, &'static str> {
+    let mut value = line.trim();
+
+    if let Some(object) = value.strip_prefix('{').and_then(|rest| rest.strip_suffix('}')) {
+        value = match object.split_once(':') {
+            Some((key, value)) if key.trim() == "\"move\"" => value.trim(),
+            _ => return Err("Expected an object like {\"move\": 5}")
+        };
+    }
+
+    match value.trim_matches('"') {
+        "pass" => Ok(Action::Pass),
+        number => match number.parse::<usize>() {
+            Ok(index) => Ok(Action::Place(index, CellState::Empty)),
+            Err(_) => Err("Expected a cell number")
+        }
+    }
+}
+
+/// Reads moves from standard input one line at a time, for other programs driving the game.
+struct LineInput {
+    options: Options,
+    /// Where to say the session has to end once standard input closes.
+    stopped: Stopped,
+}
+
+impl Strategy for LineInput {
+    fn choose(&mut self, game: &Game) -> usize {
+        loop {
+            if let Action::Place(index, _) = self.act(game) {
+                return index;
+            }
+
+            self.stopped.check();
+        }
+    }
+
+    fn act(&mut self, game: &Game) -> Action {
+        loop {
+            let mut line = String::new();
+
+            match io::stdin().read_line(&mut line) {
+                Ok(0) | Err(_) => return self.stopped.stop(input_closed(&self.options)),
+                Ok(_) => {}
+            }
+
+            let error = match parse_move_line(&line) {
+                Ok(Action::Place(index, _)) => match index_to_coord(index) {
+                    Some((row, cell)) if game.board()[row][cell] == CellState::Empty => return Action::Place(index, game.current_player().to_cell()),
+                    Some(_) => "Cell has already been played!",
+                    None => "Cell is not on the board!"
+                },
+                Ok(Action::Pass) if self.options.allow_pass => return Action::Pass,
+                Ok(_) => "Passing is not allowed!",
+                Err(error) => error
+            };
+
+            println!("{{\"error\":\"{}\"}}", error.replace('"', "\\\""));
+        }
+    }
+}
+
+/// How long [`ExternalAi`] waits for the program to answer before giving up on it.
+const EXTERNAL_AI_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A computer player run as a separate program, which can be written in any language.
+/// <br><br>
+/// On each of its turns the program is sent the game as a line of JSON, the same as `--format json` prints,
+/// and has to answer with a line holding its move in any form `--format json` accepts, such as `5` or `{"move": 5}`.
+/// A program that exits, answers with something that isn't a move or takes longer than [`EXTERNAL_AI_TIMEOUT`] ends the game with an error.
+struct ExternalAi {
+    child: process::Child,
+    input: process::ChildStdin,
+    /// Lines from the program's output, read on a thread of their own so that waiting for them can time out.
+    output: mpsc::Receiver<io::Result<String>>,
+    /// Where to say what went wrong with the program.
+    stopped: Stopped,
+}
+
+impl ExternalAi {
+    /// Starts `command`, split on whitespace into the program and its arguments.
+    fn spawn(command: &str, stopped: Stopped) -> io::Result<ExternalAi> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no command given"))?;
+
+        let mut child = process::Command::new(program)
+            .args(words)
+            .stdin(process::Stdio::piped())
+            .stdout(process::Stdio::piped())
+            .spawn()?;
+
+        let input = child.stdin.take().expect("stdin was piped");
+        let stdout = child.stdout.take().expect("stdout was piped");
+        let (sender, output) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in io::BufReader::new(stdout).lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(ExternalAi { child, input, output, stopped })
+    }
+
+    /// Stops the program and quits the game, saying what `problem` it had.
+    fn fail(&mut self, problem: &str) -> Action {
+        let _ = self.child.kill();
+        self.stopped.stop(format!("The external AI {}.", problem))
+    }
+}
+
+impl Strategy for ExternalAi {
+    fn choose(&mut self, game: &Game) -> usize {
+        loop {
+            if let Action::Place(index, _) = self.act(game) {
+                return index;
+            }
+
+            self.fail("passed when it had to place a mark");
+            self.stopped.check();
+        }
+    }
+
+    fn act(&mut self, game: &Game) -> Action {
+        if writeln!(self.input, "{}", game_json(game, None)).and_then(|_| self.input.flush()).is_err() {
+            return self.fail("stopped reading its input");
+        }
+
+        let line = match self.output.recv_timeout(EXTERNAL_AI_TIMEOUT) {
+            Ok(Ok(line)) => line,
+            Ok(Err(error)) => return self.fail(&format!("couldn't be read ({})", error)),
+            Err(mpsc::RecvTimeoutError::Timeout) => return self.fail(&format!("didn't answer within {} seconds", EXTERNAL_AI_TIMEOUT.as_secs())),
+            Err(mpsc::RecvTimeoutError::Disconnected) => return self.fail("exited without answering")
+        };
+
+        match parse_move_line(&line) {
+            Ok(Action::Place(index, _)) => Action::Place(index, game.current_player().to_cell()),
+            Ok(action) => action,
+            Err(error) => self.fail(&format!("answered {:?}, which isn't a move ({})", line.trim(), error))
+        }
+    }
+}
+
+impl Drop for ExternalAi {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Asks which mark to place during a wild game.
+fn get_mark(term: &Term, options: &Options) -> Result<CellState, String> {
+    println!("Place an X or an O? (x/o): ");
+
+    loop {
+        match read_key(term, options)? {
+            Key::Char('x' | 'X') => return Ok(CellState::Cross),
+            Key::Char('o' | 'O') => return Ok(CellState::Nought),
+            _ => {}
+        }
+    }
+}
+
+/// Reads moves from a person at the keyboard.
+struct HumanConsole {
+    options: Options,
+    /// The clock shared with the game loop, when playing with a time control.
+    clock: Option<Rc<RefCell<ChessClock>>>,
+    /// The 1-based cell the `--teach` cursor is on, which stays where this player left it between turns.
+    cursor: usize,
+    /// Where to say the session has to end once no more keys can be read.
+    stopped: Stopped,
+}
+
+impl Strategy for HumanConsole {
+    fn choose(&mut self, game: &Game) -> usize {
+        loop {
+            if let Action::Place(index, _) = self.act(game) {
+                return index;
+            }
+
+            self.stopped.check();
+        }
+    }
+
+    fn act(&mut self, game: &Game) -> Action {
+        match self.turn(game) {
+            Ok(action) => action,
+            Err(reason) => self.stopped.stop(reason)
+        }
+    }
+}
+
+impl HumanConsole {
+    /// Asks for the player's turn, failing once no more keys can be read.
+    fn turn(&mut self, game: &Game) -> Result<Action, String> {
+        let term = Term::stdout();
+
+        if let Some((index, mark)) = self.forced(game) {
+            // In wild games the move that doesn't lose can be the other player's mark
+            let with = match mark.to_player() {
+                Ok(player) if player != *game.current_player() => format!(" with an {}", player.to_letter()),
+                _ => String::new()
+            };
+
+            println!("Your only move that doesn't lose is {}{} {} play it? (y/n)", self.options.numbering.label(index), with, dash(&self.options));
+
+            if ask_yes_no(&term, &self.options)? {
+                return Ok(Action::Place(index, mark));
+            }
+        }
+
+        // The turn was drawn before the cursor could be, so draw it again with the cursor on it
+        if self.options.teach {
+            self.redraw(game);
+        }
+
+        self.prompt(game);
+
+        // The number typed so far in strict mode, waiting for Enter
+        let mut pending = String::new();
+
+        loop {
+            let key = match &self.clock {
+                Some(clock) => {
+                    let deadline = clock.borrow().deadline(*game.current_player());
+
+                    match read_key_timeout(&term, deadline.saturating_duration_since(Instant::now())) {
+                        Ok(Some(Key::Unknown)) if !term.is_term() => return Err(input_closed(&self.options)),
+                        Ok(Some(key)) => key,
+                        // The player's time is up, so there's no point waiting for a move
+                        Ok(None) => return Ok(Action::Resign),
+                        Err(_) => return Err(input_closed(&self.options))
+                    }
+                }
+                // Waiting in short steps lets the turn be drawn again to fit when the window is resized
+                None if term.is_term() => {
+                    let mut size = term.size_checked();
+
+                    loop {
+                        match read_key_timeout(&term, RESIZE_CHECK) {
+                            Ok(Some(key)) => break key,
+                            Ok(None) if term.size_checked() != size => {
+                                size = term.size_checked();
+
+                                self.redraw(game);
+                                self.prompt(game);
+
+                                if !pending.is_empty() {
+                                    show_pending(&term, &pending, &self.options);
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(_) => return Err(input_closed(&self.options))
+                        }
+                    }
+                }
+                None => read_key(&term, &self.options)?
+            };
+
+            if self.options.strict {
+                match key {
+                    Key::Char(char) if char.is_ascii_digit() => {
+                        pending.push(char);
+                        show_pending(&term, &pending, &self.options);
+                        continue;
+                    }
+                    Key::Backspace if !pending.is_empty() => {
+                        pending.pop();
+                        show_pending(&term, &pending, &self.options);
+                        continue;
+                    }
+                    Key::Enter if !pending.is_empty() => {
+                        println!();
+
+                        // Anything too big to parse can't be on the board either
+                        let index = pending.parse().unwrap_or(0);
+                        pending.clear();
+
+                        if let Some(action) = choose_cell(game, &self.options, &term, index)? {
+                            if self.confirm(&term, game, &action)? {
+                                return Ok(action);
+                            }
+                        }
+                        continue;
+                    }
+                    _ if !pending.is_empty() => {
+                        pending.clear();
+                        println!(" - cancelled");
+                    }
+                    _ => {}
+                }
+            }
+
+            match key {
+                Key::Char('h' | '?') => {
+                    show_help(&term, &self.options)?;
+
+                    self.redraw(game);
+                    self.prompt(game);
+                }
+                Key::ArrowUp | Key::ArrowDown | Key::ArrowLeft | Key::ArrowRight if self.options.teach => {
+                    self.move_cursor(key);
+
+                    self.redraw(game);
+                    self.prompt(game);
+                }
+                Key::Enter if self.options.teach => {
+                    if let Some(action) = choose_cell(game, &self.options, &term, self.options.numbering.label(self.cursor))? {
+                        if self.confirm(&term, game, &action)? {
+                            return Ok(action);
+                        }
+                    }
+                }
+                Key::Char('w') if self.options.save_slot.is_some() => {
+                    if save_to_slot(game, &self.options) {
+                        return Ok(Action::Quit);
+                    }
+
+                    self.prompt(game);
+                }
+                Key::Char('q') => {
+                    if self.confirm_quit(&term, game)? {
+                        return Ok(Action::Quit);
+                    }
+
+                    self.prompt(game);
+                }
+                Key::Char('b') if can_solve(&self.options) => {
+                    println!("{}", best_play(game, &self.options));
+
+                    self.prompt(game);
+                }
+                // Exploring would show the board, which blind games keep hidden
+                Key::Char('s') if !self.options.blind => {
+                    self.sandbox(&term, game)?;
+
+                    self.redraw(game);
+                    self.prompt(game);
+                }
+                key => {
+                    if let Some(action) = get_input(game, &self.options, &term, key)? {
+                        if self.confirm(&term, game, &action)? {
+                            return Ok(action);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// The move to offer with `--auto-forced`, see [`forced_move`].
+    /// <br><br>
+    /// The evaluator never passes and only looks a few moves ahead in rolling games, so nothing is forced in games with either.
+    fn forced(&self, game: &Game) -> Option<(usize, CellState)> {
+        if !self.options.auto_forced || self.options.allow_pass || game.rules().rolling {
+            return None;
+        }
+
+        forced_move(game)
+    }
+
+    /// Asks whether to give up on the game, unless `--no-confirm-quit` was given, saying whether to quit.
+    /// <br><br>
+    /// Games with a save slot can be saved on the way out, and any key but y (or w to save) keeps playing.
+    fn confirm_quit(&self, term: &Term, game: &Game) -> Result<bool, String> {
+        if self.options.no_confirm_quit {
+            return Ok(true);
+        }
+
+        let save = if self.options.save_slot.is_some() { ", or w to save and quit" } else { "" };
+
+        println!("Quit? Current game will be lost (y/n{})", save);
+
+        Ok(match read_key(term, &self.options)? {
+            Key::Char('y' | 'Y') => true,
+            Key::Char('w') if self.options.save_slot.is_some() => save_to_slot(game, &self.options),
+            _ => false
+        })
+    }
+
+    /// Draws the turn again, with the cursor when teaching.
+    fn redraw(&self, game: &Game) {
+        let mut marked: Vec<usize> = opponent_move(game, &self.options).into_iter().collect();
+
+        if self.options.teach {
+            marked.push(self.cursor);
+        }
+
+        draw_turn_at(&Term::stdout(), game, &self.options, self.clock.as_ref().map(|clock| clock.borrow()).as_deref(), &marked);
+    }
+
+    /// Moves the `--teach` cursor one cell in the direction of the arrow key, stopping at the edge of the board.
+    fn move_cursor(&mut self, key: Key) {
+        let (row, cell) = index_to_coord(self.cursor).expect("the cursor is always on the board");
+
+        // Mirrored boards are drawn right to left, so the arrows have to follow what's on screen
+        let (left, right) = if self.options.mirror { (Key::ArrowRight, Key::ArrowLeft) } else { (Key::ArrowLeft, Key::ArrowRight) };
+
+        let (row, cell) = match key {
+            Key::ArrowUp => (row.saturating_sub(1), cell),
+            Key::ArrowDown => ((row + 1).min(2), cell),
+            key if key == left => (row, cell.saturating_sub(1)),
+            key if key == right => (row, (cell + 1).min(2)),
+            _ => (row, cell)
+        };
+
+        self.cursor = coord_to_index(row, cell);
+    }
+
+    fn prompt(&self, game: &Game) {
+        if self.options.teach {
+            for line in teach_overlay(game, self.cursor, &self.options) {
+                println!("{}", line);
+            }
+        }
+
+        show_footer(&self.options);
+
+        let pass = if self.options.allow_pass { " (or p to pass)" } else { "" };
+        let mark = if self.options.rules.wild { String::from("a mark") } else { format!("an {}", game.current_player().to_letter()) };
+        let enter = if self.options.strict { " and press Enter" } else { "" };
+
+        let ask = match &self.options.prompt {
+            Some(template) => fill_prompt(template, game, &self.options),
+            None => format!("Player {} ({}): ", game.current_player().to_number(), game.current_player().to_letter())
+        };
+
+        println!("Please type a number{enter} to place {mark}{pass}\n{ask}", enter = enter, mark = mark, pass = pass, ask = ask);
+    }
+
+    /// In assist mode, checks the player really wants to make a move that turns a game they weren't losing into a loss.
+    /// With `--no-blunders` such moves are refused outright.
+    /// <br><br>
+    /// Returns whether the move should go ahead, prompting for another move if not.
+    fn confirm(&self, term: &Term, game: &Game, action: &Action) -> Result<bool, String> {
+        let (index, mark) = match action {
+            Action::Place(index, mark) if self.options.assist || self.options.no_blunders => (*index, *mark),
+            _ => return Ok(true)
+        };
+
+        let opponent = game.current_player().other();
+        let mut after = game.clone();
+
+        // An illegal move is turned down when it's played, there's nothing to warn about
+        match index_to_coord(index).map(|(row, cell)| after.make_move(row, cell, mark)) {
+            Some(Ok(())) => {}
+            _ => return Ok(true)
+        }
+
+        if evaluate(&after) != GameOutcome::Win(opponent) || evaluate(game) == GameOutcome::Win(opponent) {
+            return Ok(true);
+        }
+
+        if self.options.no_blunders {
+            println!("That move loses {} pick another.", dash(&self.options));
+            self.prompt(game);
+            return Ok(false);
+        }
+
+        println!("Are you sure? This lets {} win. (y/n)", opponent.to_letter());
+
+        loop {
+            match read_key(term, &self.options)? {
+                Key::Char('y' | 'Y') => return Ok(true),
+                Key::Char('n' | 'N') => {
+                    self.prompt(game);
+                    return Ok(false);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Lets the player try out moves for both sides on a copy of the game.
+    /// <br><br>
+    /// Pressing Escape throws the copy away, putting the real position back exactly as it was.
+    fn sandbox(&self, term: &Term, game: &Game) -> Result<(), String> {
+        let mut sandbox = game.clone();
+
+        loop {
+            clear_screen(term, &self.options);
+
+            println!("Sandbox - these moves don't count. Press Esc to return to the game.");
+            draw_board(sandbox.board(), &self.options);
+
+            let over = match sandbox.outcome() {
+                Some(GameOutcome::Win(player)) => {
+                    let win = check_win(sandbox.board(), sandbox.rules()).expect("a won game has a completed line");
+
+                    println!("{} would win with {}.", player.to_name(), describe_line(&win, &self.options));
+                    true
+                }
+                Some(GameOutcome::Draw) => {
+                    println!("That would be a draw.");
+                    true
+                }
+                None => {
+                    println!("Player {} ({}) to move.", sandbox.current_player().to_number(), sandbox.current_player().to_letter());
+
+                    if is_decided(&sandbox) {
+                        match evaluate(&sandbox) {
+                            GameOutcome::Win(player) => println!("The result is already decided: {} wins with best play.", player.to_name()),
+                            GameOutcome::Draw => println!("The result is already decided: nobody can complete a line.")
+                        }
+                    }
+
+                    false
+                }
+            };
+
+            match read_key(term, &self.options)? {
+                Key::Escape => return Ok(()),
+                key if !over => match get_input(&sandbox, &self.options, term, key)? {
+                    Some(Action::Place(index, mark)) => {
+                        if let Some((row, cell)) = index_to_coord(index) {
+                            // A move that can't be played just leaves the board as it was
+                            let _ = sandbox.make_move(row, cell, mark);
+                        }
+                    }
+                    Some(Action::Pass) => sandbox.pass(),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Turns a key press into the action it stands for, if it was a legal one.
+fn get_input(game: &Game, options: &Options, term: &Term, key: Key) -> Result<Option<Action>, String> {
+    if let Key::Char(char) = key {
+        if let Some(digit) = char.to_digit(10) {
+            return choose_cell(game, options, term, digit as usize);
+        }
+
+        if char == 'p' && options.allow_pass {
+            return Ok(Some(Action::Pass));
+        }
+    }
+
+    println!("Unrecognized key {} {}.", dash(options), key_hint(options));
+
+    Ok(None)
+}
+
+/// Lists the lines through `cursor` and which players can still win each of them, for `--teach`.
+/// <br><br>
+/// The counts show why some cells are worth more than others, such as (with the cursor on the centre):
+/// ```text
+/// Cell 5 is in 4 lines: X can still win 3 of them and O can still win 3.
+///   the middle column - only X can win it
+///   the middle row - open to both
+///   the main diagonal - only O can win it
+///   the anti-diagonal - open to both
+/// ```
+fn teach_overlay(game: &Game, cursor: usize, options: &Options) -> Vec<String> {
+    let board = game.board();
+    let through: Vec<&Line> = game.rules().lines().iter().filter(|line| line.iter().any(|(row, cell)| coord_to_index(*row, *cell) == cursor)).collect();
+
+    let has = |line: &Line, state: CellState| line.iter().any(|(row, cell)| board[*row][*cell] == state);
+    let open_to = |player: Player| through.iter().filter(|line| !has(line, player.other().to_cell()) && !has(line, CellState::Blocked)).count();
+
+    let mut overlay = vec![format!(
+        "Cell {} is in {} lines: X can still win {} of them and O can still win {}.",
+        options.numbering.label(cursor),
+        through.len(),
+        open_to(Player::Crosses),
+        open_to(Player::Noughts)
+    )];
+
+    for line in through {
+        let state = match (has(line, CellState::Cross), has(line, CellState::Nought)) {
+            _ if has(line, CellState::Blocked) => "blocked by a hole",
+            (false, false) => "open to both",
+            (true, false) => "only X can win it",
+            (false, true) => "only O can win it",
+            (true, true) => "blocked"
+        };
+
+        overlay.push(format!("  {} {} {}", line_name(line, 3), dash(options), state));
+    }
+
+    overlay
+}
+
+/// Writes a line to standard error about the move `player` just tried for `--echo-moves`, with the position after it,
+/// such as "X 5 ...|.X.|...".
+/// <br><br>
+/// The board on standard output is left alone, so a transcript of the moves can be compared with the one expected.
+/// A move that wasn't allowed is tagged with why, such as "O 5 rejected (Cell has already been played!) ...|.X.|...",
+/// and its position is the one it was tried in.
+fn echo_move(game: &Game, options: &Options, player: Player, attempt: &str, rejected: Option<&str>) {
+    if !options.echo_moves {
+        return;
+    }
+
+    match rejected {
+        Some(reason) => eprintln!("{} {} rejected ({}) {}", player.to_letter(), attempt, reason, board_to_line(game.board())),
+        None => eprintln!("{} {} {}", player.to_letter(), attempt, board_to_line(game.board()))
+    }
+}
+
+/// Places a mark in the cell the player typed the number of, if it's free.
+fn choose_cell(game: &Game, options: &Options, term: &Term, label: usize) -> Result<Option<Action>, String> {
+    let index = options.numbering.label(label);
+
+    Ok(match game.board().get(index) {
+        Some(CellState::Empty) => {
+            let mark = if options.rules.wild { get_mark(term, options)? } else { game.current_player().to_cell() };
+
+            Some(Action::Place(index, mark))
+        }
+        Some(state) => {
+            let reason = if *state == CellState::Blocked { "Cell is blocked!" } else { "Cell has already been played!" };
+
+            echo_move(game, options, *game.current_player(), &label.to_string(), Some(reason));
+
+            if options.blind {
+                // Only beep, telling the player which mark is there would reveal the board
+                let _ = term.write_str("\x07");
+            }
+
+            None
+        }
+        None => {
+            echo_move(game, options, *game.current_player(), &label.to_string(), Some("Cell is not on the board!"));
+
+            println!("There is no cell {} {} {}.", label, dash(options), key_hint(options));
+            None
+        }
+    })
+}
+
+/// Shows the number typed so far in strict mode, on a line of its own.
+fn show_pending(term: &Term, pending: &str, options: &Options) {
+    if options.ascii_only {
+        // A carriage return still rewrites the line, the extra space covers a digit removed with Backspace
+        let _ = term.write_str(&format!("\rCell: {} ", pending));
+    } else {
+        let _ = term.clear_line();
+        let _ = term.write_str(&format!("Cell: {}", pending));
+    }
+}
+
+/// A way of changing the rules, as listed by `--list-variants` and named in the help's modes.
+struct Variant {
+    name: &'static str,
+    /// The flag that turns it on, with its value if it takes one.
+    flag: &'static str,
+    /// The rule change in a sentence.
+    summary: &'static str,
+    /// Whether the options have it turned on.
+    active: fn(&Options) -> bool,
+}
+
+/// Every variant of the rules that can be played, in the order they're listed.
+/// <br><br>
+/// A new variant only needs adding here to show up in both places.
+const VARIANTS: [Variant; 5] = [
+    Variant {
+        name: "wild",
+        flag: "--wild",
+        summary: "Either player can place either mark, and a line wins for whoever completes it.",
+        active: |options| options.rules.wild
+    },
+    Variant {
+        name: "passing allowed",
+        flag: "--allow-pass",
+        summary: "Players can pass instead of placing a mark, and two passes in a row is a draw.",
+        active: |options| options.allow_pass
+    },
+    Variant {
+        name: "no diagonals",
+        flag: "--no-diagonals",
+        summary: "Only rows and columns win, the diagonals don't count.",
+        active: |options| !options.rules.diagonals
+    },
+    Variant {
+        name: "rolling",
+        flag: "--rolling",
+        summary: "Each player only keeps their three newest marks, so a fourth mark clears their oldest one.",
+        active: |options| options.rules.rolling
+    },
+    Variant {
+        name: "holes",
+        flag: "--hole N",
+        summary: "Cell N is a hole nobody can play in, so lines through it can never be won (give it again for more holes).",
+        active: |options| options.rules.holes.contains(&true)
+    }
+];
+
+/// Prints each of the [`VARIANTS`] with its flag and what it changes.
+fn list_variants() {
+    let name_width = VARIANTS.iter().map(|variant| variant.name.len()).max().unwrap_or(0);
+    let flag_width = VARIANTS.iter().map(|variant| variant.flag.len()).max().unwrap_or(0);
+
+    for variant in &VARIANTS {
+        println!("{:<name_width$}  {:<flag_width$}  {}", variant.name, variant.flag, variant.summary, name_width = name_width, flag_width = flag_width);
+    }
+}
+
+/// The keys a human can press on their turn, as the keys, a word or two for the footer and a description for the help.
+fn key_bindings(options: &Options) -> Vec<(&'static str, &'static str, &'static str)> {
+    let mut bindings = Vec::new();
+
+    if options.strict {
+        bindings.push(("1-9", "type cell", "type a cell number, Enter to play it and Backspace to fix it"));
+    } else {
+        bindings.push(("1-9", "place", "place a mark in that cell"));
+    }
+
+    if options.rules.wild {
+        bindings.push(("x/o", "mark", "choose which mark to place, after picking a cell"));
+    }
+
+    if options.allow_pass {
+        bindings.push(("p", "pass", "pass your turn"));
+    }
+
+    if !options.blind {
+        bindings.push(("s", "sandbox", "explore moves in a sandbox, Esc to return"));
+    }
+
+    if can_solve(options) {
+        bindings.push(("b", "best play", "show how the game goes from here if both sides play perfectly"));
+    }
+
+    if options.save_slot.is_some() {
+        bindings.push(("w", "save", "save the game to its slot and quit"));
+    }
+
+    if options.teach {
+        bindings.push(("arrows", "cursor", "move the cursor to see the lines through a cell, Enter to play it"));
+    }
+
+    bindings.push(("q", "quit", "leave the game, asking first unless --no-confirm-quit was given"));
+    bindings.push(("h/?", "help", "show this help"));
+    bindings
+}
+
+/// Whether the b key can show the best play in these games, see [`best_play`].
+/// <br><br>
+/// The line would give away a blind board, and rolling games can go on forever, so there might never be a line to show.
+fn can_solve(options: &Options) -> bool {
+    !options.blind && !options.rules.rolling
+}
+
+/// How the game goes from here with perfect play, such as "Best play: X5 O1 X9 O3 X7 — X wins".
+/// <br><br>
+/// A game that's already over only gets its result.
+fn best_play(game: &Game, options: &Options) -> String {
+    let (line, outcome) = principal_variation(game);
+
+    let result = match outcome {
+        GameOutcome::Win(player) => format!("{} wins", player.to_letter()),
+        GameOutcome::Draw => String::from("draw")
+    };
+
+    if line.is_empty() {
+        return format!("Best play: game over {} {}", dash(options), result);
+    }
+
+    let moves: Vec<String> = line
+        .iter()
+        .filter_map(|turn| match turn {
+            Move::Place(player, row, cell, mark) => Some(format!("{}{}", player.to_letter(), cell_to_text(options.numbering.label(coord_to_index(*row, *cell)), *mark, *player))),
+            Move::Pass => None
+        })
+        .collect();
+
+    format!("Best play: {} {} {}", moves.join(" "), dash(options), result)
+}
+
+/// Prints the keys from [`key_bindings`] on one line, such as "1-9 place · s sandbox · h/? help".
+fn show_footer(options: &Options) {
+    if options.hide_footer {
+        return;
+    }
+
+    let separator = if options.ascii_only { " | " } else { " · " };
+    let footer: Vec<String> = key_bindings(options).iter().map(|(keys, action, _)| format!("{} {}", keys, action)).collect();
+
+    if options.ascii_only {
+        println!("{}", footer.join(separator));
+    } else {
+        println!("{}", style(footer.join(separator)).dim());
+    }
+}
+
+/// Lists the keys that can be pressed and the rules in play, returning once any key is pressed.
+fn show_help(term: &Term, options: &Options) -> Result<(), String> {
+    clear_screen(term, options);
+
+    println!("Keys:");
+
+    for (keys, _, description) in key_bindings(options) {
+        println!("  {:<4} {}", keys, description);
+    }
+
+    println!();
+
+    let mut modes = Vec::new();
+
+    if options.blind {
+        modes.push("blind");
+    }
+
+    modes.extend(VARIANTS.iter().filter(|variant| (variant.active)(options)).map(|variant| variant.name));
+
+    if options.mirror {
+        modes.push("mirrored board");
+    }
+
+    if options.strict {
+        modes.push("strict entry");
+    }
+
+    if options.assist {
+        modes.push("assist");
+    }
+
+    if options.no_blunders {
+        modes.push("no blunders");
+    }
+
+    if modes.is_empty() {
+        modes.push("standard");
+    }
+
+    println!("Mode: {}", modes.join(", "));
+    println!();
+    println!("Press any key to return to the game.");
+
+    read_key(term, options)?;
+
+    Ok(())
+}
+
+/// How many moves `--seed-board` plays before handing over to the players, two for each side.
+const MIDGAME_MOVES: usize = 4;
+
+/// The position games start from, which is the empty board unless a random opening was asked for.
+fn starting_game(options: &Options) -> Game {
+    if let Some(saved) = &options.resume {
+        return saved.game().expect("saves are checked when they're read");
+    }
+
+    if let Some(board) = &options.position {
+        return game_from_board(board, &options.rules);
+    }
+
+    let mut game = Game::with_rules(options.rules);
+
+    let opening = match (options.random_open, options.seed_board) {
+        (Some(depth), _) => random_opening(&mut Rng::new(options.seed.unwrap_or_else(Rng::time_seed)), depth, &options.rules),
+        (None, Some(seed)) => random_midgame(&mut Rng::new(seed), MIDGAME_MOVES, &options.rules),
+        (None, None) => Vec::new()
+    };
+
+    for index in opening {
+        let (row, cell) = index_to_coord(index).expect("openings only play cells on the board");
+
+        game.make_move(row, cell, game.current_player().to_cell()).expect("openings only play empty cells");
+    }
+
+    game
+}
+
+/// Explains why the mirroring player couldn't copy the last move, if there was one to copy.
+fn mirror_broken(game: &Game, options: &Options) -> Option<String> {
+    let (row, cell) = game.history().iter().rev().find_map(|turn| match turn {
+        Move::Place(player, row, cell, _) if *player != *game.current_player() => Some((*row, *cell)),
+        _ => None
+    })?;
+
+    let copier = game.current_player().to_letter();
+    let opponent = game.current_player().other().to_letter();
+
+    if (row, cell) == (1, 1) {
+        Some(format!("The mirror breaks: {} took the centre, which is its own mirror image, so {} can't copy it.", opponent, copier))
+    } else {
+        let mirror = coord_to_index(2 - row, 2 - cell);
+
+        Some(format!("The mirror breaks: cell {}, opposite {}'s move, is already taken, so {} has to play elsewhere.", options.numbering.label(mirror), opponent, copier))
+    }
+}
+
+/// Writes the board in its one-line form to the `--board-file`, if there is one, for other programs to watch.
+/// <br><br>
+/// The board goes to a temporary file next to it first and is then renamed over it, so readers never see half a board.
+fn write_board_file(game: &Game, options: &Options) {
+    let path = match &options.board_file {
+        Some(path) => path,
+        None => return
+    };
+
+    let name = path.file_name().map_or_else(|| String::from("board"), |name| name.to_string_lossy().into_owned());
+    let temporary = path.with_file_name(format!(".{}.tmp", name));
+
+    let result = fs::write(&temporary, format!("{}\n", board_to_line(game.board()))).and_then(|_| fs::rename(&temporary, path));
+
+    if let Err(error) = result {
+        eprintln!("Warning: could not write the board to {} ({}).", path.display(), error);
+    }
+}
+
+/// Writes the finished game to the `--annotate` file, if there is one, with `result` being `X`, `O` or `draw`.
+/// <br><br>
+/// After a comment line, every move gets a tab separated line of its number, the player, the cell (or `pass`,
+/// and written like [`moves_to_text`] does for wild moves), who wins with best play from the position it leaves and the reason given by [`explain_move`], such as (with the tabs widened):
+/// ```text
+/// 1       X       5       draw    Takes the centre
+/// 2       O       1       draw    Optimal per search
+/// result  draw
+/// ```
+fn write_annotation(game: &Game, options: &Options, result: &str) {
+    let path = match &options.annotate {
+        Some(path) => path,
+        None => return
+    };
+
+    let mut text = String::from("# move\tplayer\tcell\tbest play from here\tnote\n");
+    let mut replay = Game::with_rules(*game.rules());
+
+    let value = |replay: &Game| match evaluate(replay) {
+        GameOutcome::Win(player) => player.to_letter().to_string(),
+        GameOutcome::Draw => String::from("draw")
+    };
+
+    for (number, turn) in game.history().iter().enumerate() {
+        match turn {
+            Move::Place(player, row, cell, mark) => {
+                let index = coord_to_index(*row, *cell);
+
+                let note = explain_move(&replay, index, *mark);
+
+                replay.make_move(*row, *cell, *mark).expect("the game's own moves are always legal");
+
+                text.push_str(&format!("{}\t{}\t{}\t{}\t{}\n", number + 1, player.to_letter(), cell_to_text(index, *mark, *player), value(&replay), note));
+            }
+            Move::Pass => {
+                let player = *replay.current_player();
+
+                replay.pass();
+                text.push_str(&format!("{}\t{}\tpass\t{}\t-\n", number + 1, player.to_letter(), value(&replay)));
+            }
+        }
+    }
+
+    text.push_str(&format!("result\t{}\n", result));
+
+    if let Err(error) = fs::write(path, text) {
+        eprintln!("Warning: could not write the annotated game to {} ({}).", path.display(), error);
+    }
+}
+
+/// Plays one game on the console, adding its result to the scoreboard, and hands back how it ended along with the game.
+/// <br><br>
+/// A player who quits leaves the game without a result, which ends the session. A game that can't go on, such as
+/// when the input closes or a move breaks the rules under `--on-illegal abort`, fails with why.
+/// <br><br>
+/// Each turn is asked of the [`Seating`], the same as [`play_game`] does, but the loop is this one's own:
+/// between turns it draws the board, runs the clock and offers to finish decided games, none of which the library's loop knows about.
+fn play(options: &Options, scoreboard: &mut Scoreboard, term: &Term) -> Result<(Option<GameOutcome>, Game), String> {
+    let mut game = starting_game(options);
+
+    let clock = options.clock.map(|(limit, increment)| Rc::new(RefCell::new(ChessClock::new(limit, increment))));
+    let stopped = Stopped::default();
+
+    let mut seating = Seating::new(options.crosses.strategy(options, &clock, &stopped)?, options.noughts.strategy(options, &clock, &stopped)?);
+
+    write_board_file(&game, options);
+
+    // Something to tell the players once the board has been drawn again
+    let mut notice: Option<String> = None;
+
+    // The move number, player, cell and positions searched for each move the hard computer makes, with --profile-search
+    let mut profile: Vec<(usize, Player, usize, u64)> = Vec::new();
+
+    // Auto-finishing is only offered once, a player who wants to play it out shouldn't be asked every move
+    let mut offered_finish = false;
+    let mut auto_finish = false;
+
+    loop {
+        // Only a start that's already over gets here with the game finished, which the options try to rule out
+        if let Some(outcome) = game.outcome() {
+            if !options.quiet && options.format == Format::Pretty {
+                println!("The game is already over {} there's nothing left to play.", dash(options));
+            }
+
+            return Ok((Some(outcome), game));
+        }
+
+        if options.hotseat_pass && !game.history().is_empty() {
+            pass_screen(term, *game.current_player(), options)?;
+        }
+
+        match options.format {
+            _ if options.quiet || options.brief() => {}
+            Format::Pretty => {
+                let clock = clock.as_ref().map(|clock| clock.borrow());
+
+                // Showing the board for a moment before the computer's move is picked out makes the cell flash
+                if let (Some(delay), Some(_), false) = (options.delay, opponent_move(&game, options), options.ascii_only) {
+                    draw_turn_at(term, &game, options, clock.as_deref(), &[]);
+                    thread::sleep(delay);
+                }
+
+                draw_turn(term, &game, options, clock.as_deref());
+            }
+            Format::Json => print_json(&game, None)
+        }
+
+        if let Some(notice) = notice.take().filter(|_| !options.quiet && !options.brief()) {
+            println!("{}", notice);
+        }
+
+        let mover = *game.current_player();
+
+        if !offered_finish && can_auto_finish(&game, options) {
+            offered_finish = true;
+
+            // There's nothing to watch in playing out a dead draw, so that's offered as ending here instead
+            if is_dead_draw(&game) {
+                println!("This position is a dead draw {} end now? (y/n)", dash(options));
+
+                if ask_yes_no(term, options)? {
+                    finish_game(term, &game, GameOutcome::Draw, None, options, scoreboard, &profile)?;
+                    return Ok((Some(GameOutcome::Draw), game));
+                }
+            } else {
+                println!("Result decided {} auto-finish? (y/n)", dash(options));
+
+                if ask_yes_no(term, options)? {
+                    seating = Seating::new(Box::new(MinimaxAi), Box::new(MinimaxAi));
+                    auto_finish = true;
+                }
+            }
+        }
+
+        if let Some(clock) = &clock {
+            clock.borrow_mut().start();
+        }
+
+        // The prompt goes up as soon as the strategy is asked, so this times the player rather than the drawing
+        let started = Instant::now();
+
+        // The hard computer is the same search whichever way it's asked, so profiling asks it for the count along with its move
+        let action = match (options.profile_search, options.controller(mover)) {
+            (true, Controller::Computer(Difficulty::Hard)) => {
+                let ((index, mark), positions) = MinimaxAi.choose_counted(&game);
+
+                profile.push((game.history().len() + 1, mover, index, positions));
+                Action::Place(index, mark)
+            }
+            _ => seating.seat(mover).act(&game)
+        };
+
+        let took = started.elapsed();
+
+        let out_of_time = match &clock {
+            Some(clock) => !clock.borrow_mut().stop(mover),
+            None => false
+        };
+
+        // Leaving ends the session without a result, the scores from earlier games are still saved on the way out
+        if matches!(action, Action::Quit) {
+            return match stopped.reason() {
+                Some(reason) => Err(reason),
+                None => Ok((None, game))
+            };
+        }
+
+        let resigned = out_of_time || matches!(action, Action::Resign);
+
+        // Waiting for the key comes after the clock has stopped, so reading the move costs the computer no time
+        let stepping = options.step_ai && !options.headless && options.format == Format::Pretty && options.controller(mover) != Controller::Human;
+
+        if let (true, Action::Place(index, mark), false) = (stepping, &action, resigned) {
+            println!("Computer will play {} {} press any key", cell_to_text(options.numbering.label(*index), *mark, mover), dash(options));
+            println!("Why: {}", explain_move(&game, *index, *mark));
+            read_key(term, options)?;
+        }
+
+        if options.controller(mover) == Controller::Mirror && mirror_move(&game).is_none() {
+            notice = mirror_broken(&game, options);
+        }
+
+        let forked = match (options.controller(mover), &action) {
+            // Wild moves can use either mark, so only the player's own mark makes a fork of theirs
+            (Controller::Human, Action::Place(index, mark)) if *mark == mover.to_cell() && !resigned && !auto_finish => creates_fork(game.board(), *index, mover, game.rules()),
+            _ => false
+        };
+
+        let attempt = match &action {
+            _ if resigned => String::from("resign"),
+            Action::Place(index, _) => options.numbering.label(*index).to_string(),
+            Action::Pass => String::from("pass"),
+            Action::Resign | Action::Quit => String::from("resign")
+        };
+
+        let result = match action {
+            _ if resigned => Ok(()),
+            Action::Place(index, mark) => match index_to_coord(index) {
+                Some((row, cell)) => game.make_timed_move(row, cell, mark, took),
+                None => Err("Cell is not on the board!")
+            },
+            Action::Pass if options.allow_pass => {
+                game.pass();
+                Ok(())
+            }
+            Action::Pass => Err("Passing is not allowed!"),
+            Action::Resign | Action::Quit => Ok(())
+        };
+
+        if forked && result.is_ok() {
+            notice = Some(format!("Nice {} you've created a fork!", dash(options)));
+        }
+
+        echo_move(&game, options, mover, &attempt, result.err());
+
+        let mut forfeited = false;
+
+        if let Err(error) = result {
+            let offender = format!("Player {} made an illegal move: {}", game.current_player().to_number(), error);
+
+            match options.on_illegal {
+                IllegalMovePolicy::Abort => return Err(offender),
+                IllegalMovePolicy::Skip => {
+                    eprintln!("{} {} skipping their turn.", offender, dash(options));
+                    game.pass();
+                }
+                IllegalMovePolicy::Forfeit => {
+                    eprintln!("{} {} they forfeit the game.", offender, dash(options));
+                    forfeited = true;
+                }
+            }
+        }
+
+        write_board_file(&game, options);
+
+        // How the game was won if it ended off the board
+        let by = if out_of_time {
+            Some("on time")
+        } else if resigned {
+            Some("by resignation")
+        } else if forfeited {
+            Some("by forfeit")
+        } else {
+            None
+        };
+
+        let winner = if by.is_some() { Some(mover.other()) } else { game.winner() };
+
+        match winner {
+            Some(player) => {
+                finish_game(term, &game, GameOutcome::Win(player), by, options, scoreboard, &profile)?;
+                return Ok((Some(GameOutcome::Win(player)), game));
+            }
+            None if game.outcome() == Some(GameOutcome::Draw) => {
+                finish_game(term, &game, GameOutcome::Draw, None, options, scoreboard, &profile)?;
+                return Ok((Some(GameOutcome::Draw), game));
+            }
+            // Playing a move or passing has already handed the turn to the other player
+            _ => {}
+        }
+    }
+}
+
+/// Adds the game that just ended to the scores and the `--annotate` and `--record` files, then shows how it ended.
+/// <br><br>
+/// `by` says how a game that wasn't won on the board was won, such as "by resignation". A draw before the game is over
+/// is a dead draw the players agreed to end early.
+fn finish_game(term: &Term, game: &Game, outcome: GameOutcome, by: Option<&str>, options: &Options, scoreboard: &mut Scoreboard, profile: &[(usize, Player, usize, u64)]) -> Result<(), String> {
+    let result = match outcome {
+        GameOutcome::Win(player) => {
+            scoreboard.record_win(player, options);
+            player.to_letter().to_string()
+        }
+        GameOutcome::Draw => {
+            scoreboard.draws += 1;
+            String::from("draw")
+        }
+    };
+
+    write_annotation(game, options, &result);
+    append_record(game, options, outcome);
+
+    if options.format == Format::Json {
+        print_json(game, Some(&result));
+        return Ok(());
+    }
+
+    // The game only gets its summary line
+    if options.brief() {
+        print_analysis(game, options, outcome);
+        print_search_profile(profile, options);
+        return Ok(());
+    }
+
+    // Only a win on the board has a line to pick out
+    let win = check_win(game.board(), game.rules()).filter(|_| by.is_none() && matches!(outcome, GameOutcome::Win(_)));
+
+    // Animating needs the cursor moved around, which plain ASCII output can't do
+    if let (Some(win), Some(delay), false, false) = (&win, options.delay, options.ascii_only, options.quiet) {
+        animate_win(term, game.board(), options, &win.line, delay);
+    }
+
+    // Show the move that ended the game, which also reveals the board in blind games
+    if !options.quiet {
+        clear_screen(term, options);
+    }
+
+    match &win {
+        Some(win) => draw_board_highlighted(game.board(), options, &win.line),
+        None => draw_board(game.board(), options)
+    }
+
+    // The limit only gets the credit for draws the board itself wouldn't have ended yet
+    let out_of_moves = game.rules().move_limit.is_some_and(|limit| game.history().len() >= limit) && !is_full(game.board());
+
+    match outcome {
+        GameOutcome::Win(player) => {
+            let how = match (by, &win) {
+                (Some(by), _) => format!(" {}", by),
+                (None, Some(win)) => format!(" with {}", describe_line(win, options)),
+                (None, None) => String::new()
+            };
+
+            print_banner(&format!("{} WINS", player.to_letter()), options);
+            println!("{} wins{}!", player.to_name(), how);
+        }
+        GameOutcome::Draw if game.outcome().is_none() => {
+            print_banner("DRAW", options);
+            println!("It's a draw, nobody could complete a line!");
+        }
+        GameOutcome::Draw if out_of_moves => println!("Draw {} move limit reached.", dash(options)),
+        GameOutcome::Draw => {
+            print_banner("DRAW", options);
+            println!("It's a draw!");
+        }
+    }
+
+    print_analysis(game, options, outcome);
+    print_search_profile(profile, options);
+    review_game(term, game, options)
+}
+
+/// Whether the human to move can skip to the end of the game, because its result is already settled.
+/// <br><br>
+/// [`is_decided`] never passes and only looks a few moves ahead in rolling games, so this isn't offered when either could change the result.
+fn can_auto_finish(game: &Game, options: &Options) -> bool {
+    let variant = options.rules.rolling || options.allow_pass;
+
+    options.format == Format::Pretty
+        && options.controller(*game.current_player()) == Controller::Human
+        && !variant
+        && is_decided(game)
+}
+
+/// A position from a puzzle pack, where the player to move has to find the best move.
+struct Puzzle {
+    board: Board,
+    name: Option<String>,
+}
+
+/// Reads a puzzle pack: one board per line in the one-line form, optionally followed by a name.
+/// <br><br>
+/// Blank lines and lines starting with `#` are skipped. Lines that aren't a playable position are
+/// left out too, and reported by line number in the second list.
+fn parse_puzzles(text: &str, rules: &Rules) -> (Vec<Puzzle>, Vec<String>) {
+    let mut puzzles = Vec::new();
+    let mut errors = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (position, name) = match line.split_once(char::is_whitespace) {
+            Some((position, name)) => (position, Some(name.trim().trim_start_matches('#').trim().to_string())),
+            None => (line, None)
+        };
+
+        let board = match board_from_line(position) {
+            Ok(board) => board,
+            Err(error) => {
+                errors.push(format!("Line {}: {}", number + 1, error));
+                continue;
+            }
+        };
+
+        match check_position(&board, rules) {
+            Ok(()) => puzzles.push(Puzzle { board, name: name.filter(|name| !name.is_empty()) }),
+            Err(error) => errors.push(format!("Line {}: {}", number + 1, error))
+        }
+    }
+
+    (puzzles, errors)
+}
+
+/// Makes sure a board could come up in a game that's still going, with a move left to make.
+/// <br><br>
+/// Wild games let either player place either mark, so any number of each will do.
+fn check_position(board: &Board, rules: &Rules) -> Result<(), &'static str> {
+    let crosses = board.count(&CellState::Cross);
+    let noughts = board.count(&CellState::Nought);
+
+    if (0..9).any(|i| rules.holes[i] && board.get(i + 1) != Some(&CellState::Blocked)) {
+        Err("Every --hole has to be a `#` on the board!")
+    } else if !rules.wild && crosses != noughts && crosses != noughts + 1 {
+        Err("Crosses go first, so they need as many marks as Noughts or one more!")
+    } else if check_win_variant(board, rules).is_some() {
+        Err("The game is already over!")
+    } else {
+        Ok(())
+    }
+}
+
+/// Reads the `--position-stdin` board off standard input, checking it can be played under `rules`.
+fn read_start_position(rules: &Rules) -> Result<Board, String> {
+    let board = read_position(&mut io::stdin().lock()).map_err(|error| format!("Couldn't read the position from standard input: {}", error))?;
+
+    check_position(&board, rules).map_err(|error| format!("That position can't be played ({})", error))?;
+
+    Ok(board)
+}
+
+/// Reads a board in the one-line form, such as `X.O|.X.|..O`, or as a grid of three lines.
+/// <br><br>
+/// Grid rows can space their cells out and write empty cells as their number, so a board copied from the game reads back:
+/// ```text
+/// [X] [2] [O]
+/// [4] [X] [6]
+/// [7] [8] [O]
+/// ```
+/// Only the lines making up the board are read, leaving anything after them, such as moves for `--format json`.
+fn read_position(input: &mut impl BufRead) -> Result<Board, String> {
+    let mut next_line = || -> Result<String, String> {
+        loop {
+            let mut line = String::new();
+
+            match input.read_line(&mut line) {
+                Ok(0) => return Err(String::from("the input ended before the board did")),
+                Ok(_) if line.trim().is_empty() => {}
+                Ok(_) => return Ok(line.trim().to_string()),
+                Err(error) => return Err(error.to_string())
+            }
+        }
+    };
+
+    let first = next_line()?;
+
+    if first.contains('|') {
+        return Ok(board_from_line(&first)?);
+    }
+
+    let row = |line: String| -> String {
+        line.chars()
+            .filter(|c| !c.is_whitespace() && *c != '[' && *c != ']')
+            .map(|c| if c.is_ascii_digit() { '.' } else { c })
+            .collect()
+    };
+
+    let rows = [row(first), row(next_line()?), row(next_line()?)];
+
+    Ok(board_from_line(&rows.join("|"))?)
+}
+
+/// Which cells of `board` are holes, in the form [`Rules::holes`] takes.
+fn holes_of(board: &Board) -> [bool; 9] {
+    let mut holes = [false; 9];
+
+    for (i, hole) in holes.iter_mut().enumerate() {
+        *hole = board.get(i + 1) == Some(&CellState::Blocked);
+    }
+
+    holes
+}
+
+/// A game that has reached `board`, with the marks played in turn in reading order, which checked positions always allow.
+/// <br><br>
+/// The board's `#` cells are the game's holes, which [`check_position`] makes sure include every `--hole`.
+/// In wild games the players simply take turns placing whichever mark comes next, so it's whoever didn't place the last one to move.
+fn game_from_board(board: &Board, rules: &Rules) -> Game {
+    let cells = |mark: CellState| (1..=9).filter(move |index| board.get(*index) == Some(&mark));
+    let mut crosses = cells(CellState::Cross);
+    let mut noughts = cells(CellState::Nought);
+    let mut marks = (1..=9).filter(|index| matches!(board.get(*index), Some(CellState::Cross | CellState::Nought)));
+
+    let mut game = Game::with_rules(Rules { holes: holes_of(board), ..*rules });
+
+    while let Some(index) = match (rules.wild, game.current_player()) {
+        (true, _) => marks.next(),
+        (false, Player::Crosses) => crosses.next(),
+        (false, Player::Noughts) => noughts.next()
+    } {
+        let (row, cell) = index_to_coord(index).expect("boards only have cells on the board");
+        let mark = *board.get(index).expect("boards only have cells on the board");
+
+        game.make_move(row, cell, mark).expect("each cell is only taken once");
+    }
+
+    game
+}
+
+/// Plays out a won position for the side to move, with the human defending, and says why each winning move is played.
+/// <br><br>
+/// Positions that can't be won by force are only reported, along with how they do end with best play.
+fn run_show_win(board: &Board, options: &Options) -> Result<(), String> {
+    if let Err(error) = check_position(board, &options.rules) {
+        eprintln!("That position can't be shown ({})", error);
+        process::exit(EXIT_ERROR);
+    }
+
+    let mut game = game_from_board(board, &options.rules);
+    let attacker = *game.current_player();
+
+    draw_board(game.board(), options);
+
+    match evaluate(&game) {
+        GameOutcome::Win(player) if player == attacker => {}
+        GameOutcome::Win(player) => {
+            println!("{} to move can't force a win here {} it's {} who wins with best play.", attacker.to_name(), dash(options), player.to_name());
+            return Ok(());
+        }
+        GameOutcome::Draw => {
+            println!("{} to move can't force a win here {} best play from both sides draws.", attacker.to_name(), dash(options));
+            return Ok(());
+        }
+    }
+
+    println!("{} to move can force a win. I'll play {} and you defend, try to stop me!", attacker.to_name(), attacker.to_name());
+
+    let stopped = Stopped::default();
+    let mut defender = HumanConsole { options: options.clone(), clock: None, cursor: 5, stopped: stopped.clone() };
+
+    loop {
+        let before = *game.board();
+
+        let mover = *game.current_player();
+
+        let action = if mover == attacker { MinimaxAi.act(&game) } else { defender.act(&game) };
+
+        let (index, mark) = match action {
+            Action::Place(index, mark) => (index, mark),
+            Action::Pass => {
+                game.pass();
+                println!();
+                println!("You pass.");
+                continue;
+            }
+            // The defender has given up, or quit
+            Action::Resign | Action::Quit => return stopped.reason().map_or(Ok(()), Err)
+        };
+
+        let reason = explain_move(&game, index, mark);
+        let (row, cell) = index_to_coord(index).expect("both sides only pick cells on the board");
+
+        if let Err(error) = game.make_move(row, cell, mark) {
+            println!("{}", error);
+            continue;
+        }
+
+        let played = cell_to_text(options.numbering.label(index), mark, mover);
+
+        println!();
+
+        if mover == attacker {
+            let threats = winning_moves(game.board(), attacker, game.rules());
+
+            let threatening = match threats.as_slice() {
+                _ if check_win(game.board(), game.rules()).is_some() => String::new(),
+                [] => String::new(),
+                [cell] => format!(", threatening {}", options.numbering.label(*cell)),
+                cells => format!(", threatening {} lines at once", cells.len())
+            };
+
+            println!("I play {} ({}){}.", played, reason, threatening);
+        } else {
+            println!("You play {}.", played);
+        }
+
+        draw_board_changes(&before, game.board(), options);
+
+        if let Some(win) = check_win(game.board(), game.rules()) {
+            println!("That's the win, with {}.", describe_line(&win, options));
+            return Ok(());
+        }
+    }
+}
+
+/// Presents each puzzle in turn until they've all been solved or skipped, then says how many were solved.
+fn run_puzzles(path: &Path, options: &Options) -> Result<(), String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) => {
+            eprintln!("Could not read puzzles from {} ({}).", path.display(), error);
+            process::exit(EXIT_ERROR);
+        }
+    };
+
+    let (puzzles, errors) = parse_puzzles(&text, &options.rules);
+
+    for error in &errors {
+        eprintln!("Skipping {}", error);
+    }
+
+    let term = Term::stdout();
+    let mut solved = 0;
+
+    'puzzles: for (number, puzzle) in puzzles.iter().enumerate() {
+        let game = game_from_board(&puzzle.board, &options.rules);
+        let player = *game.current_player();
+
+        clear_screen(&term, options);
+
+        match &puzzle.name {
+            Some(name) => println!("Puzzle {} of {}: {}", number + 1, puzzles.len(), name),
+            None => println!("Puzzle {} of {}", number + 1, puzzles.len())
+        }
+
+        draw_board(&puzzle.board, options);
+        println!("{} to play - find the best move. Press n to skip, or Esc to stop.", player.to_name());
+
+        loop {
+            match read_key(&term, options)? {
+                Key::Char('n') => continue 'puzzles,
+                Key::Escape => break 'puzzles,
+                Key::Char(char) if char.is_ascii_digit() => {
+                    let label = char.to_digit(10).unwrap_or(0) as usize;
+                    let index = options.numbering.label(label);
+
+                    if puzzle.board.get(index) != Some(&CellState::Empty) {
+                        println!("Cell {} isn't free - pick an empty cell.", label);
+                        continue;
+                    }
+
+                    let mark = if options.rules.wild { get_mark(&term, options)? } else { player.to_cell() };
+
+                    if rate_move(&game, index, mark) == MoveQuality::Good {
+                        solved += 1;
+                        println!("Solved! Press any key for the next puzzle.");
+
+                        read_key(&term, options)?;
+                        continue 'puzzles;
+                    } else {
+                        println!("Not quite - try again.");
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    println!("Solved {} of {} puzzles.", solved, puzzles.len());
+
+    Ok(())
+}
+
+/// Shows random positions and asks who wins each one with best play, keeping score until the player stops.
+/// <br><br>
+/// Positions have between two and six marks, so there's always something to think about and someone left to move.
+/// With `--seed` the same questions come up again.
+fn run_eval_quiz(options: &Options) -> Result<(), String> {
+    let term = Term::stdout();
+    let mut rng = Rng::new(options.seed.unwrap_or_else(Rng::time_seed));
+
+    let (mut asked, mut right, mut streak, mut best_streak) = (0, 0, 0, 0);
+
+    'questions: loop {
+        let mut game = Game::with_rules(options.rules);
+
+        let marks = 2 + rng.below(5);
+
+        for index in random_midgame(&mut rng, marks, &options.rules) {
+            game = game.clone_with_move(index).expect("random moves are always legal");
+        }
+
+        clear_screen(&term, options);
+        println!("Question {} {} {} to play. Who wins with best play?", asked + 1, dash(options), game.current_player().to_name());
+        draw_board(game.board(), options);
+        println!("Press x for Crosses, o for Noughts or d for a draw, or Esc to stop.");
+
+        let guess = loop {
+            match read_key(&term, options)? {
+                Key::Char('x' | 'X') => break GameOutcome::Win(Player::Crosses),
+                Key::Char('o' | 'O') => break GameOutcome::Win(Player::Noughts),
+                Key::Char('d' | 'D') => break GameOutcome::Draw,
+                Key::Escape | Key::Char('q') => break 'questions,
+                _ => {}
+            }
+        };
+
+        let answer = evaluate(&game);
+        let verdict = match answer {
+            GameOutcome::Win(winner) => format!("{} wins", winner.to_name()),
+            GameOutcome::Draw => String::from("it's a draw")
+        };
+
+        asked += 1;
+
+        if guess == answer {
+            right += 1;
+            streak += 1;
+            best_streak = best_streak.max(streak);
+            println!("Right, {}! That's {} in a row.", verdict, streak);
+        } else {
+            streak = 0;
+            println!("Not quite {} {}.", dash(options), verdict);
+        }
+
+        println!("Score: {} of {}. Press any key for the next one, or Esc to stop.", right, asked);
+
+        if matches!(read_key(&term, options)?, Key::Escape | Key::Char('q')) {
+            break;
+        }
+    }
+
+    println!("You got {} of {} right, with a best streak of {}.", right, asked, best_streak);
+
+    Ok(())
+}
+
+/// Prints the line `--summary-after-each` asks for after each game, such as "Game 12: O wins in 7 moves".
+fn print_summary_line(number: usize, outcome: GameOutcome, game: &Game, options: &Options) {
+    if !options.summary || options.quiet {
+        return;
+    }
+
+    let result = match outcome {
+        GameOutcome::Win(player) => format!("{} wins", player.to_letter()),
+        GameOutcome::Draw => String::from("draw")
+    };
+
+    println!("Game {}: {} in {} moves", number, result, game.history().len());
+}
+
+/// Plays `games` games between the computer players and prints how often each opening cell led to each result.
+fn run_selfplay(games: usize, options: &Options) {
+    let seed = options.seed.unwrap_or_else(Rng::time_seed);
+    let mut rng = Rng::new(seed);
+
+    // Games, crosses wins, draws and noughts wins for each opening cell
+    let mut openings = [[0usize; 4]; 9];
+
+    for number in 0..games {
+        let crosses = options.crosses.computer(Rng::new(rng.next_u64())).expect("self-play sides are always computers");
+        let noughts = options.noughts.computer(Rng::new(rng.next_u64())).expect("self-play sides are always computers");
+        let mut seating = Seating::new(crosses, noughts);
+
+        let game = Game::with_rules(options.rules);
+        let played = if options.fast_draws { play_game_fast_draws(&mut seating, game) } else { play_game(&mut seating, game) };
+
+        let (outcome, game) = match played {
+            Ok(result) => result,
+            Err(error) => {
+                eprintln!("A computer player made an illegal move: {}", error);
+                process::exit(EXIT_ERROR);
+            }
+        };
+
+        print_summary_line(number + 1, outcome, &game, options);
+
+        let opening = game.history().iter().find_map(|turn| match turn {
+            Move::Place(_, row, cell, _) => Some(coord_to_index(*row, *cell)),
+            Move::Pass => None
+        });
+
+        if let Some(index) = opening {
+            let tally = &mut openings[index - 1];
+
+            tally[0] += 1;
+            tally[match outcome {
+                GameOutcome::Win(Player::Crosses) => 1,
+                GameOutcome::Draw => 2,
+                GameOutcome::Win(Player::Noughts) => 3
+            }] += 1;
+        }
+    }
+
+    println!("Self-play: {} games, seed {}", games, seed);
+    println!("Opening  Games  X wins   Draws  O wins");
+
+    for (i, tally) in openings.iter().enumerate().filter(|(_, tally)| tally[0] > 0) {
+        let rate = |count: usize| format!("{:.1}%", 100.0 * count as f64 / tally[0] as f64);
+
+        println!("{:>7}  {:>5}  {:>6}  {:>6}  {:>6}", i + 1, tally[0], rate(tally[1]), rate(tally[2]), rate(tally[3]));
+    }
+}
+
+/// Plays `games` games between the `--crosses` and `--noughts` computers, letting each start every other game,
+/// and prints how often the `--crosses` one won, drew and lost, and how long the games went on for.
+/// <br><br>
+/// While the games are played a progress bar is drawn on standard error, when that's a terminal.
+fn run_tournament(games: usize, options: &Options) {
+    let seed = options.seed.unwrap_or_else(Rng::time_seed);
+    let mut rng = Rng::new(seed);
+
+    // Wins, draws and losses for the `--crosses` computer, the moves played over all the games, and the draws played again
+    let mut results = [0usize; 3];
+    let mut moves = 0;
+    let mut replayed = 0;
+
+    // Piped logs only get the results, the bar is for someone watching
+    let progress = Term::stderr();
+    let show_progress = progress.is_term() && !options.quiet;
+
+    for number in 0..games {
+        if show_progress {
+            draw_progress(&progress, number, games);
+        }
+
+        // The `--crosses` computer starts the even games and the `--noughts` one the odd games
+        let side = if number % 2 == 0 { Player::Crosses } else { Player::Noughts };
+        let mut replays = 0;
+
+        // Under `--draw-policy replay` a drawn game starts again, with the same side going first
+        let (outcome, game) = loop {
+            let first = options.crosses.computer(Rng::new(rng.next_u64())).expect("tournament sides are always computers");
+            let second = options.noughts.computer(Rng::new(rng.next_u64())).expect("tournament sides are always computers");
+
+            let mut seating = match side {
+                Player::Crosses => Seating::new(first, second),
+                Player::Noughts => Seating::new(second, first)
+            };
+
+            let played = match play_game(&mut seating, Game::with_rules(options.rules)) {
+                Ok(result) => result,
+                Err(error) => {
+                    if show_progress {
+                        let _ = progress.clear_line();
+                    }
+
+                    eprintln!("A computer player made an illegal move: {}", error);
+                    process::exit(EXIT_ERROR);
+                }
+            };
+
+            match played.0 {
+                GameOutcome::Draw if options.draw_policy == DrawPolicy::Replay && replays < MAX_REPLAYS => replays += 1,
+                _ => break played
+            }
+        };
+
+        replayed += replays;
+
+        // The summary line goes where the bar was, which is drawn again under it for the next game
+        if show_progress {
+            let _ = progress.clear_line();
+        }
+
+        print_summary_line(number + 1, outcome, &game, options);
+
+        results[match outcome {
+            GameOutcome::Win(winner) if winner == side => 0,
+            GameOutcome::Draw => 1,
+            GameOutcome::Win(_) => 2
+        }] += 1;
+        moves += game.history().len();
+    }
+
+    if show_progress {
+        let _ = progress.clear_line();
+    }
+
+    let rate = |count: usize| format!("{:.1}%", 100.0 * count as f64 / games as f64);
+
+    println!("Tournament: {} games, seed {}", games, seed);
+    println!("{} against {}, each starting half of the games", options.crosses.to_name(), options.noughts.to_name());
+    println!("Wins {}  Draws {}  Losses {}", rate(results[0]), rate(results[1]), rate(results[2]));
+
+    // Each win is a point, and a draw is worth whatever the policy gives it to both sides
+    let draws = results[1] as f64 * options.draw_policy.draw_points();
+
+    println!("Points: {} to {} ({})", results[0] as f64 + draws, results[2] as f64 + draws, options.draw_policy.describe());
+
+    if options.draw_policy == DrawPolicy::Replay {
+        println!("Drawn games replayed: {}", replayed);
+    }
+
+    println!("Average length: {:.1} moves", moves as f64 / games as f64);
+}
+
+/// How many characters wide the `--ai-vs-ai-tournament` progress bar is, not counting its brackets.
+const PROGRESS_WIDTH: usize = 30;
+
+/// Draws how far through its games the tournament is over the last line of `term`, such as
+/// "[#########.....................] 30/100 games".
+fn draw_progress(term: &Term, played: usize, games: usize) {
+    let filled = PROGRESS_WIDTH * played / games.max(1);
+
+    let _ = term.clear_line();
+    let _ = term.write_str(&format!("[{}{}] {}/{} games", "#".repeat(filled), ".".repeat(PROGRESS_WIDTH - filled), played, games));
+}
+
+/// Lists how many positions the hard computer searched for each of its moves, when `--profile-search` is on.
+fn print_search_profile(profile: &[(usize, Player, usize, u64)], options: &Options) {
+    if !options.profile_search || options.quiet {
+        return;
+    }
+
+    println!();
+    println!("Positions searched:");
+
+    for (number, player, index, positions) in profile {
+        println!("  Move {} ({} in {}): {}", number, player.to_letter(), options.numbering.label(*index), positions);
+    }
+
+    println!("  Total: {}", profile.iter().map(|(_, _, _, positions)| positions).sum::<u64>());
+}
+
+/// Lists how good each move of a finished game was, when the players asked for analysis.
+fn print_analysis(game: &Game, options: &Options, result: GameOutcome) {
+    if !options.analyze {
+        return;
+    }
+
+    println!();
+    println!("Analysis:");
+
+    let ratings = analyze_game(game);
+
+    for (number, (player, index, quality)) in ratings.iter().enumerate() {
+        println!("  {}. {} in {} - {}", number + 1, player.to_letter(), index, quality.to_name());
+    }
+
+    let blunders = |side: Player| ratings.iter().filter(|(player, _, quality)| *player == side && *quality == MoveQuality::Blunder).count();
+
+    println!("Blunders - X: {}, O: {}", blunders(Player::Crosses), blunders(Player::Noughts));
+
+    // The evaluator only looks a few moves ahead in rolling games, so it can't say what the result should have been
+    if !game.rules().rolling {
+        println!("{}", result_summary(game, result, options));
+    }
+}
+
+/// One sentence on whether the game ended the way best play from the start would have, and if not, which move decided it.
+/// <br><br>
+/// The deciding move is the last one that changed the result with best play to the one the game ended with, such as:
+/// ```text
+/// X won, but the game is a draw with best play from the start: O's move 4, in cell 7, was the decisive blunder.
+/// ```
+fn result_summary(game: &Game, result: GameOutcome, options: &Options) -> String {
+    let describe = |outcome: GameOutcome| match outcome {
+        GameOutcome::Win(player) => format!("{} won", player.to_letter()),
+        GameOutcome::Draw => String::from("It was drawn")
+    };
+
+    let value = |game: &Game| evaluate(game);
+
+    let mut replay = Game::with_rules(*game.rules());
+    let expected = value(&replay);
+    let mut decider = None;
+
+    for (number, turn) in game.history().iter().enumerate() {
+        let before = value(&replay);
+
+        match turn {
+            Move::Place(_, row, cell, mark) => replay.make_move(*row, *cell, *mark).expect("the game's own moves are always legal"),
+            Move::Pass => replay.pass()
+        }
+
+        let after = value(&replay);
+
+        if after != before && after == result {
+            decider = Some((number + 1, turn));
+        }
+    }
+
+    let start = match expected {
+        GameOutcome::Win(player) => format!("{} wins with best play from the start", player.to_letter()),
+        GameOutcome::Draw => String::from("the game is a draw with best play from the start")
+    };
+
+    if result == expected {
+        return format!("{}, as expected: {}.", describe(result), start);
+    }
+
+    match decider {
+        Some((number, Move::Place(player, row, cell, _))) => format!(
+            "{}, but {}: {}'s move {}, in cell {}, was the decisive blunder.",
+            describe(result),
+            start,
+            player.to_letter(),
+            number,
+            options.numbering.label(coord_to_index(*row, *cell))
+        ),
+        Some((number, Move::Pass)) => format!("{}, but {}: the pass on move {} was the decisive blunder.", describe(result), start, number),
+        // Resigning, running out of time or forfeiting ends the game without a move changing its value
+        None => format!("{}, but {}, and it still was when the game ended.", describe(result), start)
+    }
+}
+
+/// A position reached while reviewing a game, see [`review_game`].
+struct ReviewNode {
+    game: Game,
+    parent: Option<usize>,
+    /// The move played to get here from the parent, or `None` for a pass or the starting position.
+    turn: Turn,
+    /// Which line of play this position is on, the game as it was played being line 0.
+    variation: usize,
+}
+
+/// Lets the players step back and forth through the finished game and play other moves from any position.
+/// <br><br>
+/// The positions form a small tree: the game as played is the main line, and playing a different move anywhere
+/// starts a new variation from there, leaving the lines already explored as they were.
+fn review_game(term: &Term, game: &Game, options: &Options) -> Result<(), String> {
+    if !options.review || options.quiet || options.format != Format::Pretty {
+        return Ok(());
+    }
+
+    println!();
+    println!("Press any key to review the game.");
+    read_key(term, options)?;
+
+    let mut nodes = vec![ReviewNode { game: Game::with_rules(*game.rules()), parent: None, turn: None, variation: 0 }];
+
+    for turn in game_moves(game) {
+        let mut next = nodes[nodes.len() - 1].game.clone();
+
+        play_turn(&mut next, turn).expect("the game's own moves are always legal");
+        nodes.push(ReviewNode { game: next, parent: Some(nodes.len() - 1), turn, variation: 0 });
+    }
+
+    let mut current = nodes.len() - 1;
+    let mut variations = 0;
+
+    loop {
+        let node = &nodes[current];
+        let line = match node.variation {
+            0 => String::from("Main line"),
+            variation => format!("Variation {}", variation)
+        };
+
+        redraw_screen(term, options);
+        println!("{}, after {} moves", line, node.game.history().len());
+
+        match node.parent {
+            Some(parent) => draw_board_changes(nodes[parent].game.board(), node.game.board(), options),
+            None => draw_board(node.game.board(), options)
+        }
+
+        match node.game.outcome() {
+            Some(GameOutcome::Win(player)) => println!("{} has won.", player.to_name()),
+            Some(GameOutcome::Draw) => println!("The game is drawn."),
+            None => println!("{} to play.", node.game.current_player().to_name())
+        }
+
+        println!("Left/Right to step, 1-9 to try a move, m for the main line, Esc to finish.");
+
+        let children: Vec<usize> = (0..nodes.len()).filter(|&i| nodes[i].parent == Some(current)).collect();
+
+        match read_key(term, options)? {
+            Key::ArrowLeft => current = node.parent.unwrap_or(current),
+            // Going forward stays on the same line where it carries on
+            Key::ArrowRight => {
+                current = children.iter().copied().find(|&i| nodes[i].variation == node.variation).or(children.first().copied()).unwrap_or(current);
+            }
+            Key::Char('m') => {
+                while let (Some(parent), true) = (nodes[current].parent, nodes[current].variation != 0) {
+                    current = parent;
+                }
+            }
+            Key::Char(char) if char.is_ascii_digit() && node.game.outcome().is_none() => {
+                let index = options.numbering.label(char.to_digit(10).unwrap_or(0) as usize);
+
+                if node.game.board().get(index) != Some(&CellState::Empty) {
+                    continue;
+                }
+
+                let mark = if options.rules.wild { get_mark(term, options)? } else { node.game.current_player().to_cell() };
+
+                if let Some(&child) = children.iter().find(|&&i| nodes[i].turn == Some((index, mark))) {
+                    current = child;
+                    continue;
+                }
+
+                let mut next = node.game.clone();
+                play_turn(&mut next, Some((index, mark))).expect("only empty cells are played");
+
+                // The first move tried from the end of a line carries that line on, any other starts a new one
+                let variation = if children.is_empty() {
+                    node.variation
+                } else {
+                    variations += 1;
+                    variations
+                };
+
+                nodes.push(ReviewNode { game: next, parent: Some(current), turn: Some((index, mark)), variation });
+                current = nodes.len() - 1;
+            }
+            Key::Escape | Key::Char('q') => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+/// How long the key reading check in [`run_doctor`] waits for a key.
+const DOCTOR_KEY_WAIT: Duration = Duration::from_secs(5);
+
+/// Prints what `console` detects about the terminal, then checks that single key presses can be read.
+fn run_doctor(term: &Term) {
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+    let features = term.features();
+
+    println!("Terminal report:");
+    println!("  Output is a terminal: {}", yes_no(term.is_term()));
+    println!("  Someone is attending: {}", yes_no(features.is_attended()));
+    println!("  Colours supported:    {}", yes_no(features.colors_supported()));
+    println!("  Colours enabled:      {}", yes_no(console::colors_enabled()));
+    println!("  Emoji wanted:         {}", yes_no(features.wants_emoji()));
+    println!("  Cursor movement:      {}", yes_no(supports_cursor(term)));
+
+    match term.size_checked() {
+        Some((rows, columns)) => println!("  Size:                 {} columns, {} rows", columns, rows),
+        None => println!("  Size:                 unknown")
+    }
+
+    println!();
+    println!("Press any key within {} seconds to check key reading...", DOCTOR_KEY_WAIT.as_secs());
+
+    match read_key_timeout(term, DOCTOR_KEY_WAIT) {
+        Ok(Some(key)) => println!("  Key reading:          works ({:?})", key),
+        Ok(None) => println!("  Key reading:          no key pressed in time"),
+        Err(error) => println!("  Key reading:          doesn't work ({})", error)
+    }
+}
+
+/// Asks whether to play another game.
+fn ask_rematch(term: &Term, options: &Options) -> Result<bool, String> {
+    println!("Play again? (y/n)");
+
+    ask_yes_no(term, options)
+}
+
+/// Waits for y or n, with Escape counting as no, failing once no more keys can be read.
+fn ask_yes_no(term: &Term, options: &Options) -> Result<bool, String> {
+    loop {
+        match read_key(term, options)? {
+            Key::Char('y' | 'Y') => return Ok(true),
+            Key::Char('n' | 'N') | Key::Escape => return Ok(false),
+            _ => {}
+        }
+    }
+}
+
+/// Runs whatever the options ask for, exiting with a code scripts can branch on:
+/// ```text
+/// 0  nothing was played, such as after --count-games or --doctor, or the game was saved to finish later or quit
+/// 1  crosses won
+/// 2  noughts won
+/// 3  the game was drawn
+/// 4  something went wrong
+/// ```
+/// After several adaptive games, the code is for the last of them.
+fn main() {
+    let mut options = match Options::parse() {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(EXIT_ERROR);
+        }
+    };
+
+    // Parsing only looks at the flags, so a mistake in them is reported without waiting on the pipe
+    if options.position_stdin {
+        let board = read_start_position(&options.rules).unwrap_or_else(|error| exit_with(&error));
+
+        options.rules.holes = holes_of(&board);
+        options.position = Some(board);
+    }
+
+    if options.doctor {
+        run_doctor(&Term::stdout());
+        return;
+    }
+
+    if options.list_variants {
+        list_variants();
+        return;
+    }
+
+    if let Some(path) = &options.dump_tablebase {
+        let positions = tablebase(&options.rules);
+
+        let mut text = String::from("# Every reachable position up to symmetry, with the winner under perfect play\n");
+
+        for (board, value) in &positions {
+            let value = match value {
+                GameOutcome::Win(player) => player.to_letter().to_string(),
+                GameOutcome::Draw => String::from("draw")
+            };
+
+            text.push_str(&format!("{} {}\n", board_to_line(board), value));
+        }
+
+        if let Err(error) = fs::write(path, text) {
+            eprintln!("Could not write the tablebase to {} ({}).", path.display(), error);
+            process::exit(EXIT_ERROR);
+        }
+
+        println!("Wrote {} positions to {}.", positions.len(), path.display());
+        return;
+    }
+
+    // These modes keep no scores, so there's nothing to save before exiting if they fail
+    if let Some(path) = &options.puzzles {
+        run_puzzles(path, &options).unwrap_or_else(|error| exit_with(&error));
+        return;
+    }
+
+    if let Some(board) = &options.show_win {
+        run_show_win(board, &options).unwrap_or_else(|error| exit_with(&error));
+        return;
+    }
+
+    if options.eval_quiz {
+        run_eval_quiz(&options).unwrap_or_else(|error| exit_with(&error));
+        return;
+    }
+
+    if let (Some(path), Some(dir)) = (&options.replay, &options.export_replay) {
+        export_replay(path, dir, &options).unwrap_or_else(|error| exit_with(&error));
+        return;
+    }
+
+    if let Some(path) = &options.replay {
+        run_replay(path, &options).unwrap_or_else(|error| exit_with(&error));
+        return;
+    }
+
+    if let Some(path) = &options.verify {
+        let valid = match fs::read_to_string(path) {
+            Ok(text) => verify_records(&text),
+            Err(error) => {
+                eprintln!("Could not read {} ({})", path.display(), error);
+                false
+            }
+        };
+
+        process::exit(if valid { 0 } else { EXIT_ERROR });
+    }
+
+    if let Some(games) = options.selfplay {
+        run_selfplay(games, &options);
+        return;
+    }
+
+    if let Some(games) = options.tournament {
+        run_tournament(games, &options);
+        return;
+    }
+
+    if options.count_games {
+        let game = starting_game(&options);
+        let size = count_games(&game);
+
+        println!("From {}:", board_to_line(game.board()));
+        println!("  Complete games:               {}", size.games);
+        println!("  Distinct positions:           {}", size.positions);
+        println!("  Distinct positions, symmetry: {}", size.canonical_positions);
+        return;
+    }
+
+    let term = Term::stdout();
+
+    // The scores haven't been loaded yet, so a failure here has nothing to save either
+    let slot = if options.slots {
+        match run_slot_menu(&options).unwrap_or_else(|error| exit_with(&error)) {
+            Some(slot) => Some(slot),
+            None => return
+        }
+    } else {
+        offer_resume(&term, &options).unwrap_or_else(|error| exit_with(&error))
+    };
+
+    if let Some((name, saved)) = slot {
+        options.rules = saved.rules;
+        options.resume = Some(saved);
+        options.save_slot.get_or_insert(name);
+    }
+
+    let scores_path = Scoreboard::path();
+
+    let mut scoreboard = match &scores_path {
+        Some(_) if options.reset_scores => Scoreboard::default(),
+        Some(path) => Scoreboard::load(path),
+        None => {
+            eprintln!("Warning: no home directory found, scores will not be saved.");
+            Scoreboard::default()
+        }
+    };
+
+    let session = run_games(&mut options, &mut scoreboard, &term);
+
+    if let Err(error) = &session {
+        eprintln!("{}", error);
+    }
+
+    // Keep standard output to JSON lines only, so programs reading it don't trip over the totals
+    if options.format == Format::Pretty && !options.quiet {
+        println!("Crosses: {} | Noughts: {} | Draws: {}", scoreboard.crosses, scoreboard.noughts, scoreboard.draws);
+
+        if let Some(side) = options.human_side() {
+            println!("You ({}): {} | Computer ({}): {}", side.to_letter(), scoreboard.human, side.other().to_letter(), scoreboard.computer);
+        }
+    }
+
+    if let Some(path) = &scores_path {
+        if let Err(error) = scoreboard.save(path) {
+            eprintln!("Warning: could not save scores to {} ({}).", path.display(), error);
+        }
+    }
+
+    process::exit(session.map_or(EXIT_ERROR, |outcome| outcome.map_or(0, exit_code)));
+}
+
+/// Says why the program can't go on and exits with the error code.
+fn exit_with(error: &str) -> ! {
+    eprintln!("{}", error);
+    process::exit(EXIT_ERROR);
+}
+
+/// Plays games until the session ends, handing back how the last one went, or nothing if it was left early.
+/// <br><br>
+/// The scores are kept in `scoreboard` whatever happens, so [`main`] can save them even when the session fails part way.
+fn run_games(options: &mut Options, scoreboard: &mut Scoreboard, term: &Term) -> Result<Option<GameOutcome>, String> {
+    let mut played = 0;
+
+    loop {
+        let (outcome, game) = play(options, scoreboard, term)?;
+
+        let outcome = match outcome {
+            Some(outcome) => outcome,
+            None => return Ok(None)
+        };
+
+        played += 1;
+
+        print_summary_line(played, outcome, &game, options);
+
+        offer_replay(term, &game, options)?;
+        options.resume = None;
+
+        // Computer games under a limit go straight on to the next one, there's nobody to ask
+        match options.max_games {
+            Some(limit) if played >= limit => return Ok(Some(outcome)),
+            Some(_) if !options.adaptive => {
+                if let (Some(delay), Format::Pretty, false) = (options.delay, options.format, options.quiet) {
+                    thread::sleep(delay);
+                }
+
+                continue;
+            }
+            _ => {}
+        }
+
+        // Only adaptive games offer a rematch, and programs reading JSON can just run the game again
+        if !options.adaptive || options.format == Format::Json {
+            return Ok(Some(outcome));
+        }
+
+        if let GameOutcome::Win(winner) = outcome {
+            let human_won = options.controller(winner) == Controller::Human;
+
+            for controller in [&mut options.crosses, &mut options.noughts] {
+                if let Controller::Computer(level) = controller {
+                    *level = next_difficulty(*level, human_won);
+                }
+            }
+
+            if let Some(level) = options.adaptive_level() {
+                println!("Level: {}", level.to_name());
+            }
+        }
+
+        if !ask_rematch(term, options)? {
+            return Ok(Some(outcome));
+        }
+
+        // The computer takes its level with it, and the human's wins stay theirs whichever mark they play
+        println!("Swap sides? (y/n)");
+
+        if ask_yes_no(term, options)? {
+            (options.crosses, options.noughts) = (options.noughts, options.crosses);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A record of playing `moves` under `rules`, ending the way the game says or with `result` if it didn't finish.
+    fn record(rules: Rules, moves: &str, result: GameOutcome) -> GameRecord {
+        let game = replay_moves(rules, &parse_moves(moves).unwrap()).unwrap();
+
+        GameRecord::from_game(&game, game.outcome().unwrap_or(result))
+    }
+
+    #[test]
+    fn records_read_back_the_way_they_were_written() {
+        let mut holes = [false; 9];
+        holes[4] = true;
+
+        let records = [
+            record(Rules::standard(), "1 4 2 5 3", GameOutcome::Draw),
+            record(Rules { rolling: true, diagonals: false, holes, move_limit: Some(4), ..Rules::standard() }, "1 9 3 7", GameOutcome::Draw),
+            // Noughts resigned after crosses passed, so the board doesn't say who won
+            record(Rules::standard(), "5 pass", GameOutcome::Win(Player::Noughts)),
+            record(Rules::standard(), "", GameOutcome::Win(Player::Crosses)),
+            // Noughts completed the top row with crosses' mark
+            record(Rules { wild: true, rolling: true, ..Rules::standard() }, "1 2x 5o 3x", GameOutcome::Draw)
+        ];
+
+        assert_eq!(records[4].mode(), "rolling+wild");
+        assert_eq!(records[4].result, GameOutcome::Win(Player::Noughts));
+
+        let mut file = Vec::new();
+
+        for record in &records {
+            write_record(&mut file, record).unwrap();
+        }
+
+        let read = read_records(&String::from_utf8(file).unwrap()).unwrap();
+
+        assert_eq!(read.len(), records.len());
+
+        for (written, read) in records.iter().zip(&read) {
+            assert_eq!(read.date, written.date);
+            assert_eq!(read.rules, written.rules);
+            assert_eq!(read.result, written.result);
+            assert_eq!(read.moves, written.moves);
+        }
+    }
+
+    #[test]
+    fn wild_moves_keep_their_marks() {
+        let moves = parse_moves("5 1x pass 9o 3").unwrap();
+
+        assert_eq!(moves, [Some((5, CellState::Cross)), Some((1, CellState::Cross)), None, Some((9, CellState::Nought)), Some((3, CellState::Cross))]);
+        assert_eq!(moves_to_text(&moves), "5 1x pass 9 3");
+
+        // Only wild games let a player place the other mark
+        assert!(replay_moves(Rules { wild: true, ..Rules::standard() }, &moves).is_ok());
+        assert_eq!(replay_moves(Rules::standard(), &moves).err(), Some("Players can only place their own mark!"));
+
+        let saved = SavedGame { rules: Rules { wild: true, ..Rules::standard() }, moves };
+        let read = SavedGame::parse(&saved.to_text()).unwrap();
+
+        assert_eq!(read.rules, saved.rules);
+        assert_eq!(read.moves, saved.moves);
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected() {
+        let headers = [
+            "date=2026-10-14T09:30 mode=standard size=3 result=X",
+            "tictactoe mode=standard size=3 result=X",
+            "tictactoe date= mode=standard size=3 result=X",
+            "tictactoe date=2026-10-14T09:30 size=3 result=X",
+            "tictactoe date=2026-10-14T09:30 mode=standard result=X",
+            "tictactoe date=2026-10-14T09:30 mode=standard size=3",
+            "tictactoe date=2026-10-14T09:30 mode=misere size=3 result=X",
+            "tictactoe date=2026-10-14T09:30 mode=standard size=4 result=X",
+            "tictactoe date=2026-10-14T09:30 mode=standard size=3 result=win",
+            "tictactoe date=2026-10-14T09:30 mode=standard size=3 result=X result=O",
+            "tictactoe date=2026-10-14T09:30 mode=standard size=3 result=X winner=X",
+            "tictactoe date=2026-10-14T09:30 mode=standard size=3 result X",
+            "tictactoe date=2026-10-14T09:30 mode=standard size=3 result=X limit=0",
+            "tictactoe date=2026-10-14T09:30 mode=standard size=3 result=X holes=10"
+        ];
+
+        for header in headers {
+            assert!(GameRecord::parse(header, "1 4 2 5 3").is_err(), "accepted {:?}", header);
+        }
+
+        // A well formed header still has to agree with how the moves end
+        assert!(GameRecord::parse("tictactoe date=2026-10-14T09:30 mode=standard size=3 result=X", "1 4 2 5 3").is_ok());
+        assert!(GameRecord::parse("tictactoe date=2026-10-14T09:30 mode=standard size=3 result=O", "1 4 2 5 3").is_err());
+    }
+
+    #[test]
+    fn replay_pictures_show_each_move_and_strike_through_the_win() {
+        let count = |frame: &String, class: &str| frame.matches(&format!("class=\"{}\"", class)).count();
+
+        let frames = replay_frames(&record(Rules::standard(), "1 4 2 5 3", GameOutcome::Draw), &Options::default());
+
+        assert_eq!(frames.len(), 5);
+
+        for (ply, frame) in frames.iter().enumerate() {
+            let moves = ply + 1;
+
+            assert_eq!(count(frame, "cross"), moves - moves / 2, "crosses after move {}", moves);
+            assert_eq!(count(frame, "nought"), moves / 2, "noughts after move {}", moves);
+            assert_eq!(count(frame, "win"), usize::from(moves == 5), "winning line after move {}", moves);
+        }
+
+        // A resignation leaves nothing to strike through, and holes are drawn on every picture
+        let mut holes = [false; 9];
+        holes[8] = true;
+
+        let frames = replay_frames(&record(Rules { holes, ..Rules::standard() }, "1 5", GameOutcome::Win(Player::Crosses)), &Options::default());
+
+        assert!(frames.iter().all(|frame| count(frame, "hole") == 1 && count(frame, "win") == 0));
     }
 }
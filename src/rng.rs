@@ -0,0 +1,66 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A small xorshift random number generator.
+/// <br><br>
+/// It is nowhere near good enough for cryptography, but it's plenty for shuffling moves around.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        const NUDGE: u64 = 0x9E37_79B9_7F4A_7C15;
+
+        // Xorshift gets stuck on zero forever, so nudge it away, including for the one seed the nudge itself sends there
+        match seed ^ NUDGE {
+            0 => Rng { state: NUDGE },
+            state => Rng { state }
+        }
+    }
+
+    /// Seeds the generator from the current time.
+    pub fn from_time() -> Rng {
+        Rng::new(Rng::time_seed())
+    }
+
+    /// A seed taken from the current time, for when a run should be repeatable later.
+    pub fn time_seed() -> u64 {
+        match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(time) => time.as_nanos() as u64,
+            Err(_) => 0
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A number from 0 up to (but not including) 1.
+    pub fn next_f64(&mut self) -> f64 {
+        // The top 53 bits fill an f64's mantissa exactly
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A number from 0 up to (but not including) `bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_seed_gets_stuck_on_zero() {
+        for seed in [0, 1, u64::MAX, 0x9E37_79B9_7F4A_7C15] {
+            let mut rng = Rng::new(seed);
+            let numbers: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+
+            assert!(numbers.iter().all(|number| *number != 0), "seed {:#x} gave {:?}", seed, numbers);
+        }
+    }
+}
@@ -0,0 +1,661 @@
+use std::time::Duration;
+
+use crate::analysis::is_dead_draw;
+use crate::board::{coord_to_index, creates_fork, index_to_coord, line_name, winning_moves, Board, CellState, Line, Player, Rules};
+use crate::game::{Game, GameOutcome, Move};
+use crate::rng::Rng;
+
+/// What a player decided to do with their turn.
+pub enum Action {
+    /// Place a mark in the cell with the given 1-based index.
+    Place(usize, CellState),
+    Pass,
+    /// Give up the game, such as when the player's clock has run out.
+    Resign,
+    /// Stop playing altogether and leave the game unfinished, such as a person closing the program.
+    Quit,
+}
+
+/// A source of moves for one side of the game, such as a person at the keyboard or a computer player.
+/// <br><br>
+/// Only [`Strategy::choose`] has to be written, for example a player who always takes the lowest free cell:
+/// ```text
+/// struct FirstFree;
+///
+/// impl Strategy for FirstFree {
+///     fn choose(&mut self, game: &Game) -> usize {
+///         empty_cells(game.board())[0]
+///     }
+/// }
+/// ```
+/// See `examples/custom_strategy.rs` for a complete game played this way.
+pub trait Strategy {
+    /// Picks the 1-based index of the cell to play in.
+    /// This is only called while at least one cell is still empty.
+    fn choose(&mut self, game: &Game) -> usize;
+
+    /// Decides the whole turn.
+    /// <br><br>
+    /// By default this places the player's own mark in the cell from [`Strategy::choose`],
+    /// strategies that want to pass or play the other mark can override it.
+    fn act(&mut self, game: &Game) -> Action {
+        Action::Place(self.choose(game), game.player.to_cell())
+    }
+}
+
+/// The strategy playing each side, so a game loop can ask whoever's turn it is without caring what's behind them.
+/// <br><br>
+/// Anything that implements [`Strategy`] can take either seat, such as a person at the keyboard against the computer,
+/// or two computers of different kinds.
+pub struct Seating {
+    pub crosses: Box<dyn Strategy>,
+    pub noughts: Box<dyn Strategy>,
+}
+
+impl Seating {
+    pub fn new(crosses: Box<dyn Strategy>, noughts: Box<dyn Strategy>) -> Seating {
+        Seating { crosses, noughts }
+    }
+
+    /// The strategy playing for `player`.
+    pub fn seat(&mut self, player: Player) -> &mut dyn Strategy {
+        match player {
+            Player::Crosses => self.crosses.as_mut(),
+            Player::Noughts => self.noughts.as_mut()
+        }
+    }
+}
+
+/// Plays perfectly by searching through the ways the game can carry on.
+/// <br><br>
+/// The search plays by the game's own rules, placing either mark in wild games, rolling marks off the board
+/// and stopping at the move limit, but it never passes. Rolling games can go on forever, so those are only searched
+/// [`ROLLING_HORIZON`] moves ahead, and a game still going by then counts as a draw.
+pub struct MinimaxAi;
+
+/// How many moves ahead [`MinimaxAi`] looks in rolling games.
+/// <br><br>
+/// No other game lasts longer than this anyway, since a board only has nine cells to fill.
+pub const ROLLING_HORIZON: usize = 9;
+
+/// Where a search counts the positions it scores, see [`MinimaxAi::choose_counted`].
+/// <br><br>
+/// Searches that aren't being counted use `()`, which compiles the counting away.
+trait Tally {
+    fn add(&mut self);
+}
+
+impl Tally for () {
+    fn add(&mut self) {}
+}
+
+impl Tally for u64 {
+    fn add(&mut self) {
+        *self += 1;
+    }
+}
+
+impl MinimaxAi {
+    /// The same move as [`Strategy::act`], as a 1-based cell and the mark to place there,
+    /// along with how many positions were scored to find it.
+    pub fn choose_counted(&self, game: &Game) -> ((usize, CellState), u64) {
+        let mut positions = 0;
+        let choice = MinimaxAi::best_move(game, &mut positions);
+
+        (choice, positions)
+    }
+
+    /// Every move the player to move has in `game`, scored as in [`MinimaxAi::score`].
+    fn score_moves(game: &Game, tally: &mut impl Tally) -> Vec<((usize, CellState), i32)> {
+        let mut search = game.clone();
+        let depth = MinimaxAi::depth(game);
+
+        game.placements()
+            .map(|(index, mark)| ((index, mark), -MinimaxAi::score_after(&mut search, index, mark, depth, -i32::MAX, i32::MAX, tally)))
+            .collect()
+    }
+
+    fn best_move(game: &Game, tally: &mut impl Tally) -> (usize, CellState) {
+        let mut best: Option<((usize, CellState), i32)> = None;
+
+        for (choice, score) in MinimaxAi::score_moves(game, tally) {
+            match best {
+                Some((_, best_score)) if best_score >= score => {}
+                _ => best = Some((choice, score))
+            }
+        }
+
+        best.expect("there is always a free cell to choose from").0
+    }
+
+    /// How many more moves the search looks at from `game`: every one left to play, or [`ROLLING_HORIZON`] in rolling games.
+    fn depth(game: &Game) -> usize {
+        let left = if game.rules.rolling { ROLLING_HORIZON } else { game.legal_moves().count() };
+
+        match game.rules.move_limit {
+            Some(limit) => left.min(limit.saturating_sub(game.history.len())),
+            None => left
+        }
+    }
+
+    /// Scores `game` for the player about to move, with `depth` moves left to look at.
+    /// <br><br>
+    /// Wins are positive and losses negative, with quicker wins (and slower losses) scoring further from zero.
+    /// Draws score zero, and so does running out of moves to look at.
+    /// <br><br>
+    /// The score is only exact when it lies between `alpha` and `beta`. Outside them it's enough to know
+    /// the position is worse than a move already found, so the rest of its moves are skipped.
+    fn score(game: &mut Game, depth: usize, alpha: i32, beta: i32, tally: &mut impl Tally) -> i32 {
+        tally.add();
+
+        match game.outcome() {
+            Some(GameOutcome::Win(winner)) => {
+                let score = 1 + depth as i32;
+
+                if winner == game.player { score } else { -score }
+            }
+            Some(GameOutcome::Draw) => 0,
+            None => MinimaxAi::search(game, depth, alpha, beta, tally)
+        }
+    }
+
+    /// The best score the player to move can get from any of their moves, in a game that isn't over yet.
+    fn search(game: &mut Game, depth: usize, mut alpha: i32, beta: i32, tally: &mut impl Tally) -> i32 {
+        if depth == 0 {
+            return 0;
+        }
+
+        let marks = game.marks();
+        let mut best = -i32::MAX;
+
+        for index in 1..=9 {
+            if game.board.get(index) != Some(&CellState::Empty) {
+                continue;
+            }
+
+            for mark in marks {
+                best = best.max(-MinimaxAi::score_after(game, index, *mark, depth, -beta, -alpha, tally));
+                alpha = alpha.max(best);
+
+                // The opponent has a better move than the one that led here, so they'd never let this position come about
+                if alpha >= beta {
+                    return best;
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Scores the game for the opponent after the player to move places `mark` in `index`, then takes the move back.
+    fn score_after(game: &mut Game, index: usize, mark: CellState, depth: usize, alpha: i32, beta: i32, tally: &mut impl Tally) -> i32 {
+        let (row, cell) = index_to_coord(index).expect("empty cells are always on the board");
+
+        let rolled = game.place(row, cell, mark, Duration::ZERO);
+        let score = MinimaxAi::score(game, depth - 1, alpha, beta, tally);
+
+        game.unplay(rolled);
+
+        score
+    }
+
+    /// [`MinimaxAi::best_move`], checked in debug builds to never throw away a position that could be held.
+    fn decide(game: &Game) -> (usize, CellState) {
+        let choice = MinimaxAi::best_move(game, &mut ());
+
+        #[cfg(debug_assertions)]
+        check_not_losing(game, choice);
+
+        choice
+    }
+}
+
+impl Strategy for MinimaxAi {
+    fn choose(&mut self, game: &Game) -> usize {
+        MinimaxAi::decide(game).0
+    }
+
+    fn act(&mut self, game: &Game) -> Action {
+        let (index, mark) = MinimaxAi::decide(game);
+
+        Action::Place(index, mark)
+    }
+}
+
+/// Panics with the position if the move turns a game that wasn't lost into a loss for the player to move,
+/// which [`MinimaxAi`] should never do. This is a check for debug builds only.
+#[cfg(debug_assertions)]
+fn check_not_losing(game: &Game, (index, mark): (usize, CellState)) {
+    // Rolling games are only searched so far ahead, so the next move can see a loss this one couldn't
+    if game.rules.rolling || evaluate(game) == GameOutcome::Win(game.player.other()) {
+        return;
+    }
+
+    let mut after = game.clone();
+    let (row, cell) = index_to_coord(index).expect("the computer only picks cells on the board");
+
+    after.make_move(row, cell, mark).expect("the computer only picks legal moves");
+
+    assert!(
+        evaluate(&after) != GameOutcome::Win(game.player.other()),
+        "MinimaxAi lost a position it could hold by playing {} in {}",
+        index,
+        crate::board::board_to_line(&game.board)
+    );
+}
+
+/// Works out how `game` ends if both sides play perfectly from here, by its own rules and without passing.
+/// <br><br>
+/// Rolling games are only looked at [`ROLLING_HORIZON`] moves ahead, and count as drawn if nobody can force a win by then.
+pub fn evaluate(game: &Game) -> GameOutcome {
+    let mut search = game.clone();
+
+    match MinimaxAi::score(&mut search, MinimaxAi::depth(game), -i32::MAX, i32::MAX, &mut ()) {
+        score if score > 0 => GameOutcome::Win(game.player),
+        score if score < 0 => GameOutcome::Win(game.player.other()),
+        _ => GameOutcome::Draw
+    }
+}
+
+/// The moves both sides make from `game` if they play perfectly, oldest first, and how the game ends after them.
+/// <br><br>
+/// This is the game [`MinimaxAi`] plays against itself, so when several moves are as good it's one of the best lines
+/// rather than all of them. A game that's already over has no moves left, only its result.
+/// Rolling games can go on forever, so they shouldn't be asked about.
+pub fn principal_variation(game: &Game) -> (Vec<Move>, GameOutcome) {
+    let mut line = game.clone();
+
+    let outcome = loop {
+        if let Some(outcome) = line.outcome() {
+            break outcome;
+        }
+
+        let (index, mark) = MinimaxAi::best_move(&line, &mut ());
+        let (row, cell) = index_to_coord(index).expect("the computer only picks cells on the board");
+
+        line.make_move(row, cell, mark).expect("the computer only picks legal moves");
+    };
+
+    (line.history[game.history.len()..].to_vec(), outcome)
+}
+
+/// Picks moves at random, but favours better ones by how much [`MinimaxAi`] prefers them.
+/// <br><br>
+/// Each move is weighted by `exp(score / temperature)`, so a temperature near zero almost always plays the best move
+/// and a high one plays nearly at random. A temperature of zero plays exactly like [`MinimaxAi`].
+pub struct WeightedAi {
+    temperature: f64,
+    rng: Rng,
+}
+
+impl WeightedAi {
+    pub fn new(temperature: f64, rng: Rng) -> WeightedAi {
+        WeightedAi { temperature, rng }
+    }
+
+    /// The 1-based cell to play in and the mark to place there.
+    fn pick(&mut self, game: &Game) -> (usize, CellState) {
+        if self.temperature <= 0.0 {
+            return MinimaxAi::decide(game);
+        }
+
+        let scores = MinimaxAi::score_moves(game, &mut ());
+
+        // Measuring from the best score keeps the weights from overflowing at small temperatures
+        let best = scores.iter().map(|(_, score)| *score).max().expect("there is always a free cell to choose from");
+        let weights: Vec<f64> = scores.iter().map(|(_, score)| (f64::from(score - best) / self.temperature).exp()).collect();
+
+        let mut pick = self.rng.next_f64() * weights.iter().sum::<f64>();
+
+        for (&(choice, _), weight) in scores.iter().zip(&weights) {
+            if pick < *weight {
+                return choice;
+            }
+
+            pick -= weight;
+        }
+
+        // Rounding can leave a sliver past the last weight
+        scores[scores.len() - 1].0
+    }
+}
+
+impl Strategy for WeightedAi {
+    fn choose(&mut self, game: &Game) -> usize {
+        self.pick(game).0
+    }
+
+    fn act(&mut self, game: &Game) -> Action {
+        let (index, mark) = self.pick(game);
+
+        Action::Place(index, mark)
+    }
+}
+
+/// Plays any free cell at random.
+pub struct RandomAi {
+    rng: Rng,
+}
+
+impl RandomAi {
+    pub fn new(rng: Rng) -> RandomAi {
+        RandomAi { rng }
+    }
+}
+
+impl Strategy for RandomAi {
+    fn choose(&mut self, game: &Game) -> usize {
+        let cells: Vec<usize> = game.legal_moves().collect();
+
+        cells[self.rng.below(cells.len())]
+    }
+}
+
+/// Wins when it can and blocks when it has to, but otherwise plays at random.
+pub struct BlockingAi {
+    rng: Rng,
+}
+
+impl BlockingAi {
+    pub fn new(rng: Rng) -> BlockingAi {
+        BlockingAi { rng }
+    }
+}
+
+impl Strategy for BlockingAi {
+    fn choose(&mut self, game: &Game) -> usize {
+        let wins = winning_moves(&game.board, game.player, &game.rules);
+        let blocks = winning_moves(&game.board, game.player.other(), &game.rules);
+
+        let cells = if !wins.is_empty() {
+            wins
+        } else if !blocks.is_empty() {
+            blocks
+        } else {
+            game.legal_moves().collect()
+        };
+
+        cells[self.rng.below(cells.len())]
+    }
+}
+
+/// A short reason for playing in the cell with the given 1-based index, such as "Blocks O's threat on the top row".
+/// <br><br>
+/// The reasons come from the same ideas [`BlockingAi`] plays by, checked in order: winning, blocking, forking and taking the centre.
+/// Moves none of them explain get "Optimal per search".
+/// <br><br>
+/// In wild games `mark` can be the other player's, and a line of either mark wins for whoever completes it.
+/// Only winning and blocking explain moves there, since a fork of their own marks is just as good for the opponent.
+pub fn explain_move(game: &Game, index: usize, mark: CellState) -> String {
+    let player = game.player;
+    let opponent = player.other();
+
+    if let Ok(owner) = mark.to_player() {
+        if let Some(line) = threat_through(&game.board, index, owner, &game.rules) {
+            return format!("Wins on {}", line_name(&line, 3));
+        }
+    }
+
+    let threats: &[Player] = if game.rules.wild { &[Player::Crosses, Player::Noughts] } else { &[opponent] };
+
+    for owner in threats {
+        if let Some(line) = threat_through(&game.board, index, *owner, &game.rules) {
+            return format!("Blocks {}'s threat on {}", opponent.to_letter(), line_name(&line, 3));
+        }
+    }
+
+    if game.rules.wild {
+        return String::from("Optimal per search");
+    }
+
+    if creates_fork(&game.board, index, player, &game.rules) {
+        return String::from("Creates a fork");
+    }
+
+    if index == 5 {
+        return String::from("Takes the centre");
+    }
+
+    String::from("Optimal per search")
+}
+
+/// The line through the cell with the given 1-based index that `player` could complete by playing there.
+fn threat_through(board: &Board, index: usize, player: Player, rules: &Rules) -> Option<Line> {
+    let coord = index_to_coord(index)?;
+
+    rules.lines().iter().copied().find(|line| {
+        line.contains(&coord) && line.iter().filter(|(row, col)| board[*row][*col] == player.to_cell()).count() == 2
+    })
+}
+
+/// The cell opposite the opponent's last mark, straight through the centre, if it's free to play.
+/// <br><br>
+/// On a 3x3 board cell `i` mirrors to cell `10 - i`, so the centre is its own mirror and can never be copied.
+pub fn mirror_move(game: &Game) -> Option<usize> {
+    let (row, cell) = game.history.iter().rev().find_map(|turn| match turn {
+        Move::Place(player, row, cell, _) if *player != game.player => Some((*row, *cell)),
+        _ => None
+    })?;
+
+    let mirror = coord_to_index(2 - row, 2 - cell);
+
+    match game.board.get(mirror) {
+        Some(CellState::Empty) => Some(mirror),
+        _ => None
+    }
+}
+
+/// Copies the opponent by playing the mirror image of their last move, see [`mirror_move`].
+/// <br><br>
+/// This is a demonstration of why copying doesn't work: when the mirror cell is taken it falls back to the first free cell.
+pub struct MirrorAi;
+
+impl Strategy for MirrorAi {
+    fn choose(&mut self, game: &Game) -> usize {
+        match mirror_move(game) {
+            Some(index) => index,
+            None => game.legal_moves().next().expect("there is always a free cell to choose from")
+        }
+    }
+}
+
+/// How well the computer plays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Difficulty {
+    /// Plays at random, see [`RandomAi`].
+    Easy,
+    /// Takes wins and blocks threats, see [`BlockingAi`].
+    Medium,
+    /// Never loses, see [`MinimaxAi`].
+    Hard,
+}
+
+impl Difficulty {
+    pub fn to_name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard"
+        }
+    }
+
+    /// The computer player for this difficulty.
+    pub fn strategy(self, rng: Rng) -> Box<dyn Strategy> {
+        match self {
+            Difficulty::Easy => Box::new(RandomAi::new(rng)),
+            Difficulty::Medium => Box::new(BlockingAi::new(rng)),
+            Difficulty::Hard => Box::new(MinimaxAi)
+        }
+    }
+}
+
+/// The difficulty to play next after a decisive game, one step harder if the human won and one step easier if they lost.
+/// <br><br>
+/// The level never goes past [`Difficulty::Easy`] or [`Difficulty::Hard`]. Draws should leave the level where it is.
+pub fn next_difficulty(current: Difficulty, human_won: bool) -> Difficulty {
+    match (current, human_won) {
+        (Difficulty::Easy, true) => Difficulty::Medium,
+        (Difficulty::Medium, true) | (Difficulty::Hard, true) => Difficulty::Hard,
+        (Difficulty::Hard, false) => Difficulty::Medium,
+        (Difficulty::Medium, false) | (Difficulty::Easy, false) => Difficulty::Easy
+    }
+}
+
+/// Plays a fixed list of cells in order, for driving whole games from tests and scripts.
+/// <br><br>
+/// Once the list runs out the strategy resigns.
+pub struct ScriptedMoves {
+    moves: std::vec::IntoIter<usize>,
+}
+
+impl ScriptedMoves {
+    pub fn new(moves: Vec<usize>) -> ScriptedMoves {
+        ScriptedMoves { moves: moves.into_iter() }
+    }
+}
+
+impl Strategy for ScriptedMoves {
+    fn choose(&mut self, _game: &Game) -> usize {
+        self.moves.next().unwrap_or(0)
+    }
+
+    fn act(&mut self, game: &Game) -> Action {
+        match self.moves.next() {
+            Some(index) => Action::Place(index, game.player.to_cell()),
+            None => Action::Resign
+        }
+    }
+}
+
+/// Plays `game` out between the strategies in `seating`, returning how it ended along with the final position.
+/// <br><br>
+/// The game carries the rules it's played by, and can already be part way through or even over.
+/// Wins and draws are decided by [`Game::outcome`], and a resignation hands the game to the other player.
+/// Any illegal move, or a player quitting, stops the game with an error.
+pub fn play_game(seating: &mut Seating, game: Game) -> Result<(GameOutcome, Game), &'static str> {
+    play_out(seating, game, false)
+}
+
+/// The same as [`play_game`], but stops as soon as the game is a dead draw (see [`is_dead_draw`]) instead of filling the board.
+/// <br><br>
+/// The outcome is the same draw either way, only the moves that couldn't have changed it are left out of the final position.
+pub fn play_game_fast_draws(seating: &mut Seating, game: Game) -> Result<(GameOutcome, Game), &'static str> {
+    play_out(seating, game, true)
+}
+
+fn play_out(seating: &mut Seating, mut game: Game, fast_draws: bool) -> Result<(GameOutcome, Game), &'static str> {
+    loop {
+        // Checked before every move, so a game that starts out over never asks for one
+        if let Some(outcome) = game.outcome() {
+            return Ok((outcome, game));
+        }
+
+        if fast_draws && is_dead_draw(&game) {
+            return Ok((GameOutcome::Draw, game));
+        }
+
+        let mover = game.player;
+
+        let action = seating.seat(mover).act(&game);
+
+        match action {
+            Action::Place(index, mark) => {
+                let (row, cell) = index_to_coord(index).ok_or("Cell is not on the board!")?;
+
+                game.make_move(row, cell, mark)?;
+            }
+            Action::Pass => game.pass(),
+            Action::Resign => return Ok((GameOutcome::Win(mover.other()), game)),
+            Action::Quit => return Err("A player quit before the game was over!")
+        }
+    }
+}
+
+/// Plays a game where each side makes the given moves in order, returning how it ended.
+/// <br><br>
+/// A side that runs out of moves before the game is over resigns.
+///
+/// # Panics
+/// If one of the moves is off the board or on a cell that's already taken.
+pub fn run_game_with_moves(x_moves: Vec<usize>, o_moves: Vec<usize>) -> GameOutcome {
+    let mut seating = Seating::new(Box::new(ScriptedMoves::new(x_moves)), Box::new(ScriptedMoves::new(o_moves)));
+    let (outcome, _) = play_game(&mut seating, Game::new()).expect("scripted moves must be legal");
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::board_to_line;
+
+    /// A game by `rules` after each cell in turn is played with the mover's own mark.
+    fn after(rules: Rules, cells: &[usize]) -> Game {
+        let mut game = Game::with_rules(rules);
+
+        for index in cells {
+            game = game.clone_with_move(*index).unwrap();
+        }
+
+        game
+    }
+
+    #[test]
+    fn minimax_never_loses_to_random_from_either_seat() {
+        for seed in 0..4 {
+            for minimax in [Player::Crosses, Player::Noughts] {
+                let random: Box<dyn Strategy> = Box::new(RandomAi::new(Rng::new(seed)));
+                let mut seating = match minimax {
+                    Player::Crosses => Seating::new(Box::new(MinimaxAi), random),
+                    Player::Noughts => Seating::new(random, Box::new(MinimaxAi))
+                };
+
+                let (outcome, game) = play_game(&mut seating, Game::new()).expect("both players only pick empty cells");
+
+                assert_ne!(outcome, GameOutcome::Win(minimax.other()), "lost with seed {} as {}", seed, minimax.to_letter());
+                assert_eq!(game.outcome(), Some(outcome));
+            }
+        }
+    }
+
+    #[test]
+    fn the_search_plays_by_each_variant() {
+        // Crosses can finish noughts' middle row for themselves, but only in wild games
+        let wild = after(Rules { wild: true, ..Rules::standard() }, &[1, 4, 9, 5]);
+
+        assert_eq!(evaluate(&wild), GameOutcome::Win(Player::Crosses));
+        assert!(matches!(MinimaxAi.act(&wild), Action::Place(6, CellState::Nought)));
+        assert_eq!(evaluate(&after(Rules::standard(), &[1, 4, 9, 5])), GameOutcome::Draw);
+
+        // The top row needs the cross in cell 1, which crosses' next mark rolls off the board
+        assert_eq!(evaluate(&after(Rules::standard(), &[1, 5, 2, 8, 9, 4])), GameOutcome::Win(Player::Crosses));
+        assert_eq!(evaluate(&after(Rules { rolling: true, ..Rules::standard() }, &[1, 5, 2, 8, 9, 4])), GameOutcome::Draw);
+
+        // Crosses win after noughts' edge reply, but not before the seventh move
+        for (limit, outcome) in [(6, GameOutcome::Draw), (7, GameOutcome::Win(Player::Crosses))] {
+            let game = after(Rules { move_limit: Some(limit), ..Rules::standard() }, &[1, 2]);
+
+            assert_eq!(evaluate(&game), outcome, "with a limit of {}", limit);
+        }
+    }
+
+    #[test]
+    fn seating_asks_the_side_to_move() {
+        // The mirror copies 1 and 2 into 9 and 8, leaving the top row open for the script
+        let mut seating = Seating::new(Box::new(ScriptedMoves::new(vec![1, 2, 3])), Box::new(MirrorAi));
+
+        let (outcome, game) = play_game(&mut seating, Game::new()).expect("both players only pick empty cells");
+
+        assert_eq!(outcome, GameOutcome::Win(Player::Crosses));
+        assert_eq!(board_to_line(game.board()), "XXX|...|.OO");
+    }
+
+    #[test]
+    fn scripted_games_end_by_the_real_rules() {
+        assert_eq!(run_game_with_moves(vec![1, 2, 3], vec![4, 5]), GameOutcome::Win(Player::Crosses));
+        assert_eq!(run_game_with_moves(vec![5, 9, 2, 4, 7], vec![1, 3, 8, 6]), GameOutcome::Draw);
+
+        // Crosses runs out of moves after the first and resigns
+        assert_eq!(run_game_with_moves(vec![5], vec![1, 2]), GameOutcome::Win(Player::Noughts));
+    }
+}
@@ -0,0 +1,48 @@
+//! Runs the game with its input already closed, as happens in CI and scripts that send it nothing.
+
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Runs the game with `args` and nothing on standard input, giving its exit code and what it printed to standard error.
+/// <br><br>
+/// The game is killed if it hasn't finished within a few seconds, which fails the test rather than hanging it.
+fn run_without_input(args: &[&str]) -> (Option<i32>, String) {
+    // A home of its own, so the test never reads or writes the real scores and saves
+    let home = std::env::temp_dir().join(format!("tictactoe-empty-input-{}-{}", std::process::id(), args.join("_")));
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tic_tac_toe"))
+        .args(args)
+        .env("HOME", &home)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the game should start");
+
+    let started = Instant::now();
+
+    while child.try_wait().expect("the game should be running").is_none() {
+        if started.elapsed() > Duration::from_secs(10) {
+            child.kill().expect("the game should be running");
+            panic!("the game was still waiting for input after 10 seconds with {:?}", args);
+        }
+
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    let output = child.wait_with_output().expect("the game has finished");
+    let _ = std::fs::remove_dir_all(&home);
+
+    (output.status.code(), String::from_utf8_lossy(&output.stderr).into_owned())
+}
+
+#[test]
+fn empty_input_exits_instead_of_waiting() {
+    for args in [&[][..], &["--format", "json"], &["--noughts", "hard"]] {
+        let (code, errors) = run_without_input(args);
+
+        assert_eq!(code, Some(4), "exit code with {:?}", args);
+        assert!(errors.contains("Input closed"), "no message with {:?}: {}", args, errors);
+    }
+}